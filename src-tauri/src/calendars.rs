@@ -0,0 +1,336 @@
+//! Non-Gregorian calendars for `{year@hebrew}` and `{@hebrew}` ... `{/}`.
+//!
+//! Everything is arithmetic, so no data files or network are needed. The
+//! Hebrew and tabular Islamic algorithms follow Reingold & Dershowitz,
+//! *Calendrical Calculations*; the Persian one is the 33-year-cycle break
+//! table used by jalaali-js, which matches the official (astronomical)
+//! calendar from 560 to 3798 AD. Dates are passed around as day counts from
+//! 0001-01-01 ("fixed" dates), which is chrono's `num_days_from_ce`.
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Calendar {
+    Gregorian,
+    Hebrew,
+    /// The tabular (arithmetic) Islamic calendar; observed dates can differ by a day.
+    Islamic,
+    /// Solar Hijri.
+    Persian,
+    /// Gregorian months and days, years counted from the current era.
+    Japanese,
+    /// Thai solar: Gregorian plus 543 years.
+    Buddhist,
+    /// Republic of China (Minguo): Gregorian minus 1911 years.
+    Roc,
+}
+
+/// Names accepted after `@`. Lowercase only, so zone names like `ROC` and
+/// `Japan` keep meaning zones.
+pub const CALENDARS: &[(&str, Calendar)] = &[
+    ("gregorian", Calendar::Gregorian),
+    ("hebrew", Calendar::Hebrew),
+    ("islamic", Calendar::Islamic),
+    ("persian", Calendar::Persian),
+    ("japanese", Calendar::Japanese),
+    ("buddhist", Calendar::Buddhist),
+    ("roc", Calendar::Roc),
+];
+
+impl Calendar {
+    pub fn from_name(name: &str) -> Option<Calendar> {
+        CALENDARS.iter().find(|(n, _)| *n == name).map(|(_, c)| *c)
+    }
+
+    pub fn name(self) -> &'static str {
+        CALENDARS.iter().find(|(_, c)| *c == self).map_or("gregorian", |(n, _)| n)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CalendarDate {
+    pub year: i32,
+    /// 1-based; Hebrew months count from Tishrei, so a leap year has 13.
+    pub month: u32,
+    pub day: u32,
+    /// Transliterated month name, or `None` where the locale's Gregorian names apply.
+    pub month_name: Option<&'static str>,
+    pub era: &'static str,
+}
+
+/// `date` in `calendar`, or `None` outside the range the calendar is defined
+/// for here (Persian outside 560–3798 AD, Japanese before Meiji).
+pub fn convert(calendar: Calendar, date: NaiveDate) -> Option<CalendarDate> {
+    let gregorian = |year: i32, era| CalendarDate { year, month: date.month(), day: date.day(), month_name: None, era };
+    Some(match calendar {
+        Calendar::Gregorian if date.year() <= 0 => gregorian(1 - date.year(), "BC"),
+        Calendar::Gregorian => gregorian(date.year(), "AD"),
+        Calendar::Buddhist => gregorian(date.year() + 543, "BE"),
+        Calendar::Roc if date.year() <= 1911 => gregorian(1912 - date.year(), "Before Minguo"),
+        Calendar::Roc => gregorian(date.year() - 1911, "Minguo"),
+        Calendar::Japanese => {
+            let (era, start) = JAPANESE_ERAS
+                .iter()
+                .map(|&(era, y, m, d)| (era, NaiveDate::from_ymd_opt(y, m, d).unwrap_or(NaiveDate::MAX)))
+                .find(|(_, start)| *start <= date)?;
+            gregorian(date.year() - start.year() + 1, era)
+        }
+        Calendar::Hebrew => hebrew(fixed(date)),
+        Calendar::Islamic => islamic(fixed(date)),
+        Calendar::Persian => persian(date)?,
+    })
+}
+
+fn fixed(date: NaiveDate) -> i64 {
+    i64::from(date.num_days_from_ce())
+}
+
+/// Era names and first days, newest first.
+const JAPANESE_ERAS: &[(&str, i32, u32, u32)] = &[
+    ("Reiwa", 2019, 5, 1),
+    ("Heisei", 1989, 1, 8),
+    ("Showa", 1926, 12, 25),
+    ("Taisho", 1912, 7, 30),
+    ("Meiji", 1868, 9, 8),
+];
+
+// Hebrew. Months are numbered from Nisan (1) internally, as in the
+// reference algorithms, and renumbered from Tishrei on the way out.
+
+/// Fixed date of 1 Tishrei AM 1 (7 October 3761 BC, Julian).
+const HEBREW_EPOCH: i64 = -1_373_427;
+
+const HEBREW_MONTHS: [&str; 13] = [
+    "Nisan", "Iyar", "Sivan", "Tammuz", "Av", "Elul", "Tishrei", "Cheshvan", "Kislev", "Tevet", "Shevat", "Adar", "Adar II",
+];
+
+fn hebrew_leap(year: i64) -> bool {
+    (7 * year + 1).rem_euclid(19) < 7
+}
+
+fn hebrew_last_month(year: i64) -> i64 {
+    if hebrew_leap(year) { 13 } else { 12 }
+}
+
+/// Days from the epoch to the molad of Tishrei, with the weekday postponement.
+fn hebrew_elapsed_days(year: i64) -> i64 {
+    let months = (235 * year - 234).div_euclid(19);
+    let parts = 12084 + 13753 * months;
+    let days = 29 * months + parts.div_euclid(25920);
+    if (3 * (days + 1)).rem_euclid(7) < 3 { days + 1 } else { days }
+}
+
+/// Delays that keep years within the allowed lengths.
+fn hebrew_year_correction(year: i64) -> i64 {
+    let (ny0, ny1, ny2) = (hebrew_elapsed_days(year - 1), hebrew_elapsed_days(year), hebrew_elapsed_days(year + 1));
+    if ny2 - ny1 == 356 {
+        2
+    } else if ny1 - ny0 == 382 {
+        1
+    } else {
+        0
+    }
+}
+
+fn hebrew_new_year(year: i64) -> i64 {
+    HEBREW_EPOCH + hebrew_elapsed_days(year) + hebrew_year_correction(year)
+}
+
+fn hebrew_month_length(month: i64, year: i64) -> i64 {
+    let year_length = hebrew_new_year(year + 1) - hebrew_new_year(year);
+    let short = matches!(month, 2 | 4 | 6 | 10 | 13)
+        || (month == 12 && !hebrew_leap(year))
+        || (month == 8 && !matches!(year_length, 355 | 385))
+        || (month == 9 && matches!(year_length, 353 | 383));
+    if short { 29 } else { 30 }
+}
+
+fn fixed_from_hebrew(year: i64, month: i64, day: i64) -> i64 {
+    let months_before: i64 = if month < 7 {
+        (7..=hebrew_last_month(year)).chain(1..month).map(|m| hebrew_month_length(m, year)).sum()
+    } else {
+        (7..month).map(|m| hebrew_month_length(m, year)).sum()
+    };
+    hebrew_new_year(year) + months_before + day - 1
+}
+
+fn hebrew(date: i64) -> CalendarDate {
+    // Mean year length is 35975351/98496 days.
+    let approx = ((date - HEBREW_EPOCH) * 98496).div_euclid(35_975_351) + 1;
+    let year = if hebrew_new_year(approx) <= date { approx } else { approx - 1 };
+    let start = if date < fixed_from_hebrew(year, 1, 1) { 7 } else { 1 };
+    let month = (start..=hebrew_last_month(year))
+        .find(|&m| date <= fixed_from_hebrew(year, m, hebrew_month_length(m, year)))
+        .unwrap_or(start);
+    let day = date - fixed_from_hebrew(year, month, 1) + 1;
+    let name = match month {
+        12 if hebrew_leap(year) => "Adar I",
+        m => HEBREW_MONTHS[(m - 1) as usize],
+    };
+    CalendarDate {
+        year: year as i32,
+        month: (if month >= 7 { month - 6 } else { month + hebrew_last_month(year) - 6 }) as u32,
+        day: day as u32,
+        month_name: Some(name),
+        era: "AM",
+    }
+}
+
+// Tabular Islamic, civil epoch, with leap years 2, 5, 7, 10, 13, 16, 18,
+// 21, 24, 26 and 29 of each 30-year cycle.
+
+/// Fixed date of 1 Muharram AH 1 (16 July 622, Julian).
+const ISLAMIC_EPOCH: i64 = 227_015;
+
+const ISLAMIC_MONTHS: [&str; 12] = [
+    "Muharram",
+    "Safar",
+    "Rabi' al-awwal",
+    "Rabi' al-thani",
+    "Jumada al-awwal",
+    "Jumada al-thani",
+    "Rajab",
+    "Sha'ban",
+    "Ramadan",
+    "Shawwal",
+    "Dhu al-Qa'dah",
+    "Dhu al-Hijjah",
+];
+
+fn fixed_from_islamic(year: i64, month: i64, day: i64) -> i64 {
+    day + 29 * (month - 1) + (6 * month - 1).div_euclid(11) + (year - 1) * 354 + (3 + 11 * year).div_euclid(30) + ISLAMIC_EPOCH - 1
+}
+
+fn islamic(date: i64) -> CalendarDate {
+    let year = (30 * (date - ISLAMIC_EPOCH) + 10646).div_euclid(10631);
+    let prior = date - fixed_from_islamic(year, 1, 1);
+    let month = (11 * prior + 330).div_euclid(325);
+    let day = date - fixed_from_islamic(year, month, 1) + 1;
+    CalendarDate {
+        year: year as i32,
+        month: month as u32,
+        day: day as u32,
+        month_name: Some(ISLAMIC_MONTHS[(month - 1) as usize]),
+        era: "AH",
+    }
+}
+
+// Persian (Solar Hijri).
+
+const PERSIAN_MONTHS: [&str; 12] = [
+    "Farvardin", "Ordibehesht", "Khordad", "Tir", "Mordad", "Shahrivar", "Mehr", "Aban", "Azar", "Dey", "Bahman", "Esfand",
+];
+
+/// Years where the 33-year leap pattern restarts.
+const PERSIAN_BREAKS: [i32; 20] = [
+    -61, 9, 38, 199, 426, 686, 756, 818, 1111, 1181, 1210, 1635, 2060, 2097, 2192, 2262, 2324, 2394, 2456, 3178,
+];
+
+/// For Persian `year`: whether it is a leap year, and the March day (in
+/// Gregorian `year + 621`) it starts on.
+fn persian_year(year: i32) -> Option<(bool, u32)> {
+    if year < PERSIAN_BREAKS[0] || year >= PERSIAN_BREAKS[PERSIAN_BREAKS.len() - 1] {
+        return None;
+    }
+    let gy = year + 621;
+    let mut leap_j = -14;
+    let mut jp = PERSIAN_BREAKS[0];
+    let mut jump = 0;
+    for &jm in &PERSIAN_BREAKS[1..] {
+        jump = jm - jp;
+        if year < jm {
+            break;
+        }
+        leap_j += jump / 33 * 8 + jump % 33 / 4;
+        jp = jm;
+    }
+    let mut n = year - jp;
+    leap_j += n / 33 * 8 + (n % 33 + 3) / 4;
+    if jump % 33 == 4 && jump - n == 4 {
+        leap_j += 1;
+    }
+    let leap_g = gy / 4 - (gy / 100 + 1) * 3 / 4 - 150;
+    let march = 20 + leap_j - leap_g;
+    if jump - n < 6 {
+        n = n - jump + (jump + 4) / 33 * 33;
+    }
+    // Years since the last leap year; -1 stands for 4.
+    let since_leap = ((n + 1) % 33 - 1) % 4;
+    Some((since_leap == 0, march as u32))
+}
+
+fn persian(date: NaiveDate) -> Option<CalendarDate> {
+    let mut year = date.year() - 621;
+    let (_, march) = persian_year(year)?;
+    let nowruz = NaiveDate::from_ymd_opt(date.year(), 3, march)?;
+    let mut k = (date - nowruz).num_days();
+    let (month, day) = if (0..=185).contains(&k) {
+        (1 + k / 31, k % 31 + 1)
+    } else {
+        if k < 0 {
+            year -= 1;
+            k += 179 + i64::from(persian_year(year)?.0);
+        } else {
+            k -= 186;
+        }
+        (7 + k / 30, k % 30 + 1)
+    };
+    Some(CalendarDate {
+        year,
+        month: month as u32,
+        day: day as u32,
+        month_name: Some(PERSIAN_MONTHS[(month - 1) as usize]),
+        era: "AP",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    /// (calendar, Gregorian date, year, month, day, month name, era)
+    type Row = (Calendar, (i32, u32, u32), i32, u32, u32, Option<&'static str>, &'static str);
+
+    const TABLE: &[Row] = &[
+        // Rosh Hashanah 5785.
+        (Calendar::Hebrew, (2024, 10, 3), 5785, 1, 1, Some("Tishrei"), "AM"),
+        (Calendar::Hebrew, (2024, 10, 2), 5784, 13, 29, Some("Elul"), "AM"),
+        // 5784 is a leap year: Adar I is the 6th month from Tishrei.
+        (Calendar::Hebrew, (2024, 3, 1), 5784, 6, 21, Some("Adar I"), "AM"),
+        // Nowruz 1404.
+        (Calendar::Persian, (2025, 3, 21), 1404, 1, 1, Some("Farvardin"), "AP"),
+        (Calendar::Persian, (2025, 3, 20), 1403, 12, 30, Some("Esfand"), "AP"),
+        // Tabular 1 Muharram 1446; the observed date was a day earlier.
+        (Calendar::Islamic, (2024, 7, 8), 1446, 1, 1, Some("Muharram"), "AH"),
+        (Calendar::Islamic, (2024, 7, 7), 1445, 12, 30, Some("Dhu al-Hijjah"), "AH"),
+        (Calendar::Japanese, (2019, 4, 30), 31, 4, 30, None, "Heisei"),
+        (Calendar::Japanese, (2019, 5, 1), 1, 5, 1, None, "Reiwa"),
+        (Calendar::Japanese, (1989, 1, 7), 64, 1, 7, None, "Showa"),
+        (Calendar::Japanese, (1989, 1, 8), 1, 1, 8, None, "Heisei"),
+        (Calendar::Japanese, (1868, 9, 8), 1, 9, 8, None, "Meiji"),
+        (Calendar::Buddhist, (2024, 1, 1), 2567, 1, 1, None, "BE"),
+        (Calendar::Roc, (2024, 1, 1), 113, 1, 1, None, "Minguo"),
+        (Calendar::Roc, (1911, 12, 31), 1, 12, 31, None, "Before Minguo"),
+        (Calendar::Gregorian, (-43, 3, 15), 44, 3, 15, None, "BC"),
+    ];
+
+    #[test]
+    fn published_dates() {
+        for &(calendar, (y, m, d), year, month, day, month_name, era) in TABLE {
+            let expected = CalendarDate { year, month, day, month_name, era };
+            assert_eq!(convert(calendar, date(y, m, d)), Some(expected), "{:?} {}-{}-{}", calendar, y, m, d);
+        }
+    }
+
+    #[test]
+    fn outside_the_supported_range() {
+        assert_eq!(convert(Calendar::Japanese, date(1868, 9, 7)), None);
+        assert_eq!(convert(Calendar::Persian, date(400, 1, 1)), None);
+    }
+}
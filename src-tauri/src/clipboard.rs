@@ -0,0 +1,120 @@
+//! Clipboard writes with an HTML flavour alongside the plain text, so one
+//! copy pastes as a `<time>` element in rich editors and as text elsewhere.
+
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::error::TimestampError;
+use crate::history::{self, CopyContext, CopySource};
+use crate::markup;
+use crate::presets;
+use crate::timestamp::{format_instant, is_lenient, resolve_zone, InstantInput};
+
+/// A preset's "copy as" setting, and the `copyAs` preference for the tray's
+/// quick copy.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CopyAs {
+    /// Plain text only.
+    #[default]
+    Text,
+    /// An HTML `<time>` element, with the plain text as the fallback flavour.
+    Html,
+}
+
+/// Write `text`, and `html` where given. Platforms or clipboards without
+/// HTML support get the plain text.
+pub(crate) fn write<R: Runtime>(app: &AppHandle<R>, text: &str, html: Option<&str>) -> Result<(), TimestampError> {
+    let clipboard = app.clipboard();
+    if let Some(html) = html {
+        if clipboard.write_html(html, Some(text)).is_ok() {
+            return Ok(());
+        }
+    }
+    clipboard.write_text(text).map_err(|e| TimestampError::Clipboard { message: e.to_string() })
+}
+
+/// The HTML flavour for a preset's rendered `text`. The `html` keyword
+/// already renders a `<time>` element, so it's used as is rather than
+/// wrapped (and escaped) a second time.
+fn html_flavour(copy_as: CopyAs, format: &str, at: &DateTime<FixedOffset>, text: &str) -> Option<String> {
+    match copy_as {
+        CopyAs::Text => None,
+        CopyAs::Html if format.trim() == "html" => Some(text.to_string()),
+        CopyAs::Html => Some(markup::time_element(at, text)),
+    }
+}
+
+#[tauri::command]
+pub fn copy_rich(
+    app: AppHandle,
+    text: String,
+    html: String,
+    context: Option<CopyContext>,
+) -> Result<(), TimestampError> {
+    write(&app, &text, Some(&html))?;
+    history::record(&app, &text, context.unwrap_or_default());
+    Ok(())
+}
+
+/// Render the preset with id or name `name` for now, in its own zone and
+/// locale if it has them, and copy it as the preset asks. Returns the plain
+/// text that was copied.
+#[tauri::command]
+pub fn copy_preset(
+    app: AppHandle,
+    name: String,
+    timezone: Option<String>,
+    source: Option<CopySource>,
+) -> Result<String, TimestampError> {
+    let preset = presets::load(&app)
+        .into_iter()
+        .find(|p| p.id == name || p.name == name)
+        .ok_or_else(|| TimestampError::InvalidSetting { name: "preset".into(), message: format!("Unknown preset '{}'", name) })?;
+    let now = Utc::now();
+    let timezone = preset.timezone.or(timezone);
+    let (tz, _) = resolve_zone(&app, timezone.clone(), is_lenient(&app, None))?;
+    let rendered =
+        format_instant(app.clone(), InstantInput::Utc(now), preset.format.clone(), timezone, preset.locale, None, None, None)?;
+    let html = html_flavour(preset.copy_as, &preset.format, &now.with_timezone(&tz).fixed_offset(), &rendered.value);
+    write(&app, &rendered.value, html.as_deref())?;
+    let context = CopyContext {
+        preset: Some(preset.name),
+        timezone: Some(rendered.timezone.name),
+        instant: Some(InstantInput::Utc(now)),
+        source: source.unwrap_or_default(),
+    };
+    history::record(&app, &rendered.value, context);
+    Ok(rendered.value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at() -> DateTime<FixedOffset> {
+        FixedOffset::east_opt(3600).unwrap().with_ymd_and_hms(2023, 11, 14, 23, 13, 20).unwrap()
+    }
+
+    #[test]
+    fn text_presets_have_no_html() {
+        assert_eq!(html_flavour(CopyAs::Text, "{date}", &at(), "14"), None);
+    }
+
+    #[test]
+    fn html_presets_wrap_the_text() {
+        assert_eq!(
+            html_flavour(CopyAs::Html, "{month} {date}", &at(), "Nov <14>").as_deref(),
+            Some("<time datetime=\"2023-11-14T23:13:20+01:00\">Nov &lt;14&gt;</time>")
+        );
+    }
+
+    #[test]
+    fn the_html_keyword_is_not_wrapped_twice() {
+        let element = markup::time_element(&at(), "Nov. 14, 2023 11:13 PM");
+        assert_eq!(html_flavour(CopyAs::Html, "html", &at(), &element), Some(element));
+    }
+}
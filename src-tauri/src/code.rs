@@ -0,0 +1,421 @@
+//! `format_to_code`: the equivalent of a ThymeStamp format in other
+//! languages' date formatting APIs, built on the field mapping in `convert`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::convert::{self, escape, lookup, quote_runs, write_with, ConvertedFormat, Dialect, Piece, Unit, Untranslatable, DOTNET, STRFTIME};
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CodeLanguage {
+    /// chrono's `format`.
+    Rust,
+    /// `datetime.strftime`.
+    Python,
+    /// date-fns `format`.
+    #[serde(alias = "javascript")]
+    DateFns,
+    /// `Intl.DateTimeFormat` options.
+    Intl,
+    /// `time.Time.Format` reference layout.
+    Go,
+    /// PostgreSQL `to_char`.
+    Postgres,
+    /// MySQL `DATE_FORMAT`.
+    Mysql,
+    /// SQLite `strftime`.
+    Sqlite,
+    /// SQL Server `FORMAT`, which takes .NET format strings.
+    SqlServer,
+}
+
+impl CodeLanguage {
+    fn label(self) -> &'static str {
+        match self {
+            CodeLanguage::Rust => "chrono",
+            CodeLanguage::Python => "Python strftime",
+            CodeLanguage::DateFns => "date-fns",
+            CodeLanguage::Intl => "Intl.DateTimeFormat",
+            CodeLanguage::Go => "Go time.Format",
+            CodeLanguage::Postgres => "PostgreSQL to_char",
+            CodeLanguage::Mysql => "MySQL DATE_FORMAT",
+            CodeLanguage::Sqlite => "SQLite strftime",
+            CodeLanguage::SqlServer => "SQL Server FORMAT",
+        }
+    }
+}
+
+/// chrono accepts everything in the strftime table plus these.
+const CHRONO_EXTRA: &[(&str, Unit)] = &[("%3f", Unit::Millis)];
+
+const DATE_FNS: &[(&str, Unit)] = &[
+    ("yyyy", Unit::Year),
+    ("yy", Unit::YearShort),
+    ("MMMM", Unit::Month),
+    ("MMM", Unit::MonthAbb),
+    ("MM", Unit::MonthNum2),
+    ("M", Unit::MonthNum),
+    ("dd", Unit::Day2),
+    ("d", Unit::Day),
+    ("do", Unit::DayOrdinal),
+    ("EEEE", Unit::Weekday),
+    ("EEE", Unit::WeekdayAbb),
+    ("HH", Unit::Hour24Pad),
+    ("H", Unit::Hour24),
+    ("hh", Unit::Hour12Pad),
+    ("h", Unit::Hour12),
+    ("mm", Unit::Minute2),
+    ("m", Unit::Minute),
+    ("ss", Unit::Second2),
+    ("s", Unit::Second),
+    ("SSS", Unit::Millis),
+    ("a", Unit::Period),
+    ("xxx", Unit::OffsetColon),
+    ("xx", Unit::Offset),
+    ("II", Unit::IsoWeek2),
+    ("I", Unit::IsoWeek),
+    ("RRRR", Unit::IsoWeekYear),
+    ("Q", Unit::Quarter),
+    ("t", Unit::EpochSeconds),
+    ("T", Unit::EpochMillis),
+];
+
+const GO: &[(&str, Unit)] = &[
+    ("2006", Unit::Year),
+    ("06", Unit::YearShort),
+    ("January", Unit::Month),
+    ("Jan", Unit::MonthAbb),
+    ("01", Unit::MonthNum2),
+    ("1", Unit::MonthNum),
+    ("02", Unit::Day2),
+    ("2", Unit::Day),
+    ("_2", Unit::DaySpace),
+    ("Monday", Unit::Weekday),
+    ("Mon", Unit::WeekdayAbb),
+    ("15", Unit::Hour24Pad),
+    ("03", Unit::Hour12Pad),
+    ("3", Unit::Hour12),
+    ("04", Unit::Minute2),
+    ("4", Unit::Minute),
+    ("05", Unit::Second2),
+    ("5", Unit::Second),
+    // Only valid right after '.' or ','; checked in `go_layout`.
+    ("000", Unit::Millis),
+    ("PM", Unit::Period),
+    ("-07:00", Unit::OffsetColon),
+    ("-0700", Unit::Offset),
+    ("MST", Unit::ZoneAbb),
+    ("002", Unit::DayOfYear3),
+];
+
+/// Go reads these anywhere in a layout, so literal text must avoid them.
+const GO_ELEMENTS: &[&str] = &["Jan", "Mon", "MST", "PM", "pm", "-07", "Z07", "_2", "0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
+
+const POSTGRES: &[(&str, Unit)] = &[
+    ("YYYY", Unit::Year),
+    ("YY", Unit::YearShort),
+    ("FMMonth", Unit::Month),
+    ("Mon", Unit::MonthAbb),
+    ("FMMM", Unit::MonthNum),
+    ("MM", Unit::MonthNum2),
+    ("FMDD", Unit::Day),
+    ("DD", Unit::Day2),
+    ("FMDDth", Unit::DayOrdinal),
+    ("FMDay", Unit::Weekday),
+    ("Dy", Unit::WeekdayAbb),
+    ("FMHH24", Unit::Hour24),
+    ("HH24", Unit::Hour24Pad),
+    ("FMHH12", Unit::Hour12),
+    ("HH12", Unit::Hour12Pad),
+    ("FMMI", Unit::Minute),
+    ("MI", Unit::Minute2),
+    ("FMSS", Unit::Second),
+    ("SS", Unit::Second2),
+    ("MS", Unit::Millis),
+    ("AM", Unit::Period),
+    ("TZH:TZM", Unit::OffsetColon),
+    ("TZHTZM", Unit::Offset),
+    ("TZ", Unit::ZoneAbb),
+    ("FMDDD", Unit::DayOfYear),
+    ("DDD", Unit::DayOfYear3),
+    ("FMIW", Unit::IsoWeek),
+    ("IW", Unit::IsoWeek2),
+    ("IYYY", Unit::IsoWeekYear),
+    ("Q", Unit::Quarter),
+];
+
+const MYSQL: &[(&str, Unit)] = &[
+    ("%Y", Unit::Year),
+    ("%y", Unit::YearShort),
+    ("%M", Unit::Month),
+    ("%b", Unit::MonthAbb),
+    ("%c", Unit::MonthNum),
+    ("%m", Unit::MonthNum2),
+    ("%e", Unit::Day),
+    ("%d", Unit::Day2),
+    ("%D", Unit::DayOrdinal),
+    ("%W", Unit::Weekday),
+    ("%a", Unit::WeekdayAbb),
+    ("%k", Unit::Hour24),
+    ("%H", Unit::Hour24Pad),
+    ("%l", Unit::Hour12),
+    ("%h", Unit::Hour12Pad),
+    ("%i", Unit::Minute2),
+    ("%s", Unit::Second2),
+    ("%p", Unit::Period),
+    ("%j", Unit::DayOfYear3),
+    ("%v", Unit::IsoWeek2),
+];
+
+const SQLITE: &[(&str, Unit)] = &[
+    ("%Y", Unit::Year),
+    ("%m", Unit::MonthNum2),
+    ("%d", Unit::Day2),
+    ("%H", Unit::Hour24Pad),
+    ("%M", Unit::Minute2),
+    ("%S", Unit::Second2),
+    ("%j", Unit::DayOfYear3),
+    ("%s", Unit::EpochSeconds),
+];
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeSnippet {
+    pub language: CodeLanguage,
+    pub code: String,
+    /// Tokens and text the snippet can't reproduce; the snippet leaves them
+    /// as literal text (or drops them, for Intl).
+    pub unsupported: Vec<Untranslatable>,
+}
+
+/// An expression in `language` that formats a date the way `format` does.
+#[tauri::command]
+pub fn format_to_code(format: String, language: CodeLanguage) -> CodeSnippet {
+    to_code(&format, language)
+}
+
+pub fn to_code(format: &str, language: CodeLanguage) -> CodeSnippet {
+    let (pieces, mut unsupported) = convert::read(format, Dialect::Thymestamp);
+    let label = language.label();
+    let write = |table: &'static [(&'static str, Unit)], escape: &dyn Fn(&str) -> String| {
+        write_with(&pieces, Dialect::Thymestamp, label, |u| lookup(table, u), escape)
+    };
+    let percent = |s: &str| s.replace('%', "%%");
+    let (converted, wrap): (ConvertedFormat, fn(&str) -> String) = match language {
+        CodeLanguage::Rust => (
+            write_with(&pieces, Dialect::Thymestamp, label, |u| lookup(CHRONO_EXTRA, u).or_else(|| lookup(STRFTIME, u)), percent),
+            |p| format!("dt.format({}).to_string()", double_quoted(p)),
+        ),
+        CodeLanguage::Python => (write(STRFTIME, &percent), |p| format!("dt.strftime({})", double_quoted(p))),
+        CodeLanguage::DateFns => (
+            write(DATE_FNS, &|s| quote_runs(s, |c| c.is_ascii_alphabetic() || c == '\'', |run| format!("'{}'", run.replace('\'', "''")))),
+            |p| format!("format(date, {})", double_quoted(p)),
+        ),
+        CodeLanguage::Go => (write(GO, &str::to_string), |p| format!("t.Format({})", double_quoted(p))),
+        CodeLanguage::Postgres => (
+            write(POSTGRES, &|s| quote_runs(s, |c| c.is_ascii_alphanumeric() || c == '"', |run| format!("\"{}\"", run.replace('"', "\\\"")))),
+            |p| format!("to_char(ts, {})", sql_quoted(p)),
+        ),
+        CodeLanguage::Mysql => (write(MYSQL, &percent), |p| format!("DATE_FORMAT(ts, {})", sql_quoted(p))),
+        CodeLanguage::Sqlite => (write(SQLITE, &percent), |p| format!("strftime({}, ts)", sql_quoted(p))),
+        CodeLanguage::SqlServer => (write(DOTNET, &|s| escape(s, Dialect::Dotnet)), |p| format!("FORMAT(ts, {})", sql_quoted(p))),
+        CodeLanguage::Intl => {
+            let (code, fields) = intl(&pieces);
+            unsupported.extend(fields);
+            return CodeSnippet { language, code, unsupported };
+        }
+    };
+    unsupported.extend(converted.untranslatable);
+    if language == CodeLanguage::Go {
+        unsupported.extend(go_layout(&pieces));
+    }
+    CodeSnippet { language, code: wrap(&converted.pattern), unsupported }
+}
+
+/// Go has no escaping, so literal text that looks like a layout element and
+/// fractional seconds without a leading separator can't be expressed.
+fn go_layout(pieces: &[Piece]) -> Vec<Untranslatable> {
+    let mut problems = Vec::new();
+    let mut previous: Option<&Piece> = None;
+    for piece in pieces {
+        match piece {
+            Piece::Literal(s) if GO_ELEMENTS.iter().any(|e| s.contains(e)) => problems.push(Untranslatable {
+                text: s.clone(),
+                reason: "literal text would be read as part of the Go layout".into(),
+            }),
+            Piece::Field(Unit::Millis, source) if !matches!(previous, Some(Piece::Literal(s)) if s.ends_with(['.', ','])) => {
+                problems.push(Untranslatable {
+                    text: source.clone(),
+                    reason: "Go only formats fractional seconds after '.' or ','".into(),
+                })
+            }
+            _ => {}
+        }
+        previous = Some(piece);
+    }
+    problems
+}
+
+/// `Intl.DateTimeFormat` picks order and punctuation from the locale, so
+/// only the fields carry over.
+fn intl(pieces: &[Piece]) -> (String, Vec<Untranslatable>) {
+    let mut options: Vec<(&str, &str)> = Vec::new();
+    let mut unsupported = Vec::new();
+    let mut set = |key: &'static str, value: &'static str| {
+        if !options.iter().any(|(k, _)| *k == key) {
+            options.push((key, value));
+        }
+    };
+    for piece in pieces {
+        match piece {
+            Piece::Literal(s) if s.chars().any(|c| c.is_alphanumeric()) => unsupported.push(Untranslatable {
+                text: s.clone(),
+                reason: "Intl.DateTimeFormat takes separators and word order from the locale".into(),
+            }),
+            Piece::Literal(_) => {}
+            Piece::Field(unit, source) => match unit {
+                Unit::Year => set("year", "numeric"),
+                Unit::YearShort => set("year", "2-digit"),
+                Unit::Month => set("month", "long"),
+                Unit::MonthAbb => set("month", "short"),
+                Unit::MonthNum => set("month", "numeric"),
+                Unit::MonthNum2 => set("month", "2-digit"),
+                Unit::Day => set("day", "numeric"),
+                Unit::Day2 => set("day", "2-digit"),
+                Unit::Weekday => set("weekday", "long"),
+                Unit::WeekdayAbb => set("weekday", "short"),
+                Unit::Hour24 | Unit::Hour24Pad => {
+                    set("hour", if *unit == Unit::Hour24 { "numeric" } else { "2-digit" });
+                    set("hourCycle", "h23");
+                }
+                Unit::Hour12 | Unit::Hour12Pad | Unit::Period => {
+                    set("hour", if *unit == Unit::Hour12Pad { "2-digit" } else { "numeric" });
+                    set("hourCycle", "h12");
+                }
+                Unit::Minute | Unit::Minute2 => set("minute", "2-digit"),
+                Unit::Second | Unit::Second2 => set("second", "2-digit"),
+                Unit::Millis => set("fractionalSecondDigits", "3"),
+                Unit::ZoneAbb => set("timeZoneName", "short"),
+                Unit::ZoneLong => set("timeZoneName", "long"),
+                Unit::OffsetColon => set("timeZoneName", "longOffset"),
+                _ => unsupported.push(Untranslatable {
+                    text: source.clone(),
+                    reason: format!("no equivalent in {}", CodeLanguage::Intl.label()),
+                }),
+            },
+            Piece::Unknown(source, reason) => unsupported.push(Untranslatable {
+                text: source.clone(),
+                reason: format!("{} ({})", reason, Dialect::Thymestamp.label()),
+            }),
+        }
+    }
+    let fields: Vec<String> = options
+        .iter()
+        .map(|(k, v)| if *k == "fractionalSecondDigits" { format!("{}: {}", k, v) } else { format!("{}: \"{}\"", k, v) })
+        .collect();
+    let code = format!("new Intl.DateTimeFormat(undefined, {{ {} }}).format(date)", fields.join(", "));
+    (code, unsupported)
+}
+
+fn double_quoted(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn sql_quoted(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LONG: &str = "{day}, {month} {date} {year} {time24} 100%";
+    const ISO: &str = "{year}-{month-num-pad}-{date:pad2}T{hours24:pad2}:{minutes}:{seconds}.{milliseconds}";
+
+    fn code(format: &str, language: CodeLanguage) -> String {
+        let snippet = to_code(format, language);
+        assert_eq!(snippet.unsupported, [], "{:?}", language);
+        snippet.code
+    }
+
+    fn unsupported(format: &str, language: CodeLanguage) -> Vec<String> {
+        to_code(format, language).unsupported.into_iter().map(|u| u.text).collect()
+    }
+
+    #[test]
+    fn rust() {
+        assert_eq!(code(LONG, CodeLanguage::Rust), r#"dt.format("%A, %B %-d %Y %H:%M 100%%").to_string()"#);
+        assert_eq!(code(ISO, CodeLanguage::Rust), r#"dt.format("%Y-%m-%dT%H:%M:%S.%3f").to_string()"#);
+    }
+
+    #[test]
+    fn python() {
+        assert_eq!(code(LONG, CodeLanguage::Python), r#"dt.strftime("%A, %B %-d %Y %H:%M 100%%")"#);
+        assert_eq!(unsupported(ISO, CodeLanguage::Python), ["{milliseconds}"]);
+    }
+
+    #[test]
+    fn date_fns() {
+        assert_eq!(code(LONG, CodeLanguage::DateFns), r#"format(date, "EEEE, MMMM d yyyy HH:mm 100%")"#);
+        assert_eq!(code(ISO, CodeLanguage::DateFns), r#"format(date, "yyyy-MM-dd'T'HH:mm:ss.SSS")"#);
+        assert_eq!(code("{month} o'clock", CodeLanguage::DateFns), r#"format(date, "MMMM 'o''clock'")"#);
+    }
+
+    #[test]
+    fn intl() {
+        let snippet = to_code(LONG, CodeLanguage::Intl);
+        assert_eq!(
+            snippet.code,
+            r#"new Intl.DateTimeFormat(undefined, { weekday: "long", month: "long", day: "numeric", year: "numeric", hour: "2-digit", hourCycle: "h23", minute: "2-digit" }).format(date)"#
+        );
+        // Literal words can't be placed; punctuation is the locale's.
+        assert_eq!(unsupported(LONG, CodeLanguage::Intl), [" 100%"]);
+        assert!(to_code(ISO, CodeLanguage::Intl).code.contains("fractionalSecondDigits: 3"));
+    }
+
+    #[test]
+    fn go() {
+        assert_eq!(code(ISO, CodeLanguage::Go), r#"t.Format("2006-01-02T15:04:05.000")"#);
+        assert_eq!(code("{month} {date} at {time} {period}", CodeLanguage::Go), r#"t.Format("January 2 at 3:04 PM")"#);
+        // Digits in literal text would be read as layout elements.
+        assert_eq!(unsupported(LONG, CodeLanguage::Go), [" 100%"]);
+        assert_eq!(unsupported("{seconds}{milliseconds}", CodeLanguage::Go), ["{milliseconds}"]);
+    }
+
+    #[test]
+    fn postgres() {
+        assert_eq!(code(LONG, CodeLanguage::Postgres), r#"to_char(ts, 'FMDay, FMMonth FMDD YYYY HH24:MI "100"%')"#);
+        assert_eq!(code(ISO, CodeLanguage::Postgres), r#"to_char(ts, 'YYYY-MM-DD"T"HH24:MI:SS.MS')"#);
+    }
+
+    #[test]
+    fn mysql() {
+        assert_eq!(code(LONG, CodeLanguage::Mysql), "DATE_FORMAT(ts, '%W, %M %e %Y %H:%i 100%%')");
+        assert_eq!(unsupported(ISO, CodeLanguage::Mysql), ["{milliseconds}"]);
+    }
+
+    #[test]
+    fn sqlite() {
+        assert_eq!(code("{year}-{month-num-pad}-{date:pad2} {unix}", CodeLanguage::Sqlite), "strftime('%Y-%m-%d %s', ts)");
+        assert_eq!(unsupported(LONG, CodeLanguage::Sqlite), ["{day}", "{month}", "{date}"]);
+    }
+
+    #[test]
+    fn sql_server() {
+        assert_eq!(code(LONG, CodeLanguage::SqlServer), r"FORMAT(ts, 'dddd, MMMM d yyyy HH\:mm 100\%')");
+        assert_eq!(code(ISO, CodeLanguage::SqlServer), r"FORMAT(ts, 'yyyy-MM-dd\THH\:mm\:ss.fff')");
+    }
+
+    #[test]
+    fn untranslatable_tokens_stay_as_text() {
+        let snippet = to_code("{month} {date} (week {week})", CodeLanguage::Postgres);
+        assert_eq!(snippet.code, r#"to_char(ts, 'FMMonth FMDD ("week" {"week"})')"#);
+        assert_eq!(snippet.unsupported.len(), 1);
+        assert_eq!(snippet.unsupported[0].text, "{week}");
+        assert_eq!(snippet.unsupported[0].reason, "no equivalent field (ThymeStamp)");
+        let python = to_code("{unix-ms} {nope}", CodeLanguage::Python);
+        assert_eq!(python.code, r#"dt.strftime("{unix-ms} {nope}")"#);
+        assert_eq!(python.unsupported.iter().filter(|u| u.text == "{unix-ms}").count(), 1);
+        assert!(python.unsupported.iter().any(|u| u.text == "{nope}"));
+    }
+}
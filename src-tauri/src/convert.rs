@@ -0,0 +1,661 @@
+//! Translate format patterns between ThymeStamp brace tokens and other
+//! tools' syntaxes.
+//!
+//! Every dialect is read into a list of [`Piece`]s (literal text or a
+//! dialect-neutral [`Unit`]) and written back out from that list. Anything
+//! the source can express but the target can't is reported in
+//! `untranslatable` and kept in the output as literal text, so it stays
+//! visible instead of vanishing.
+
+use serde::{Deserialize, Serialize};
+
+use crate::template::{Node, Template, Token};
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Dialect {
+    Thymestamp,
+    /// moment.js; day.js uses the same tokens.
+    #[serde(alias = "dayjs")]
+    Moment,
+    /// Java `DateTimeFormatter`.
+    Java,
+    /// .NET custom date and time format strings.
+    Dotnet,
+    /// Python `strftime`.
+    #[serde(alias = "python")]
+    Strftime,
+}
+
+impl Dialect {
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Dialect::Thymestamp => "ThymeStamp",
+            Dialect::Moment => "moment/day.js",
+            Dialect::Java => "Java DateTimeFormatter",
+            Dialect::Dotnet => ".NET",
+            Dialect::Strftime => "strftime",
+        }
+    }
+
+    /// Symbols for each unit. Where a unit has several spellings, the first
+    /// is the one written out.
+    fn table(self) -> &'static [(&'static str, Unit)] {
+        match self {
+            Dialect::Thymestamp => THYMESTAMP,
+            Dialect::Moment => MOMENT,
+            Dialect::Java => JAVA,
+            Dialect::Dotnet => DOTNET,
+            Dialect::Strftime => STRFTIME,
+        }
+    }
+}
+
+/// A date or time field, independent of any one syntax.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Unit {
+    Year,
+    YearShort,
+    Month,
+    MonthAbb,
+    MonthNum,
+    MonthNum2,
+    Day,
+    Day2,
+    DaySpace,
+    DayOrdinal,
+    Weekday,
+    WeekdayAbb,
+    Hour24,
+    Hour24Pad,
+    Hour12,
+    Hour12Pad,
+    Minute,
+    Minute2,
+    Second,
+    Second2,
+    Millis,
+    Period,
+    OffsetColon,
+    Offset,
+    ZoneAbb,
+    ZoneId,
+    ZoneLong,
+    DayOfYear,
+    DayOfYear3,
+    IsoWeek,
+    IsoWeek2,
+    IsoWeekYear,
+    Quarter,
+    EpochSeconds,
+    EpochMillis,
+    /// `HH:mm` and `h:mm` read as one field. Only ThymeStamp has a token for
+    /// them, so they are formed just before writing it; see `merge_clocks`.
+    Clock24,
+    Clock12,
+}
+
+const THYMESTAMP: &[(&str, Unit)] = &[
+    ("{year}", Unit::Year),
+    ("{year-short}", Unit::YearShort),
+    ("{month}", Unit::Month),
+    ("{month:abb3}", Unit::MonthAbb),
+    ("{month-abb}", Unit::MonthAbb),
+    ("{month-num}", Unit::MonthNum),
+    ("{month-num-pad}", Unit::MonthNum2),
+    ("{date}", Unit::Day),
+    ("{date:pad2}", Unit::Day2),
+    ("{date:pad-space}", Unit::DaySpace),
+    ("{day-ordinal}", Unit::DayOrdinal),
+    ("{day}", Unit::Weekday),
+    ("{day:abb3}", Unit::WeekdayAbb),
+    ("{day-abb}", Unit::WeekdayAbb),
+    ("{hours24}", Unit::Hour24),
+    ("{hours24:pad2}", Unit::Hour24Pad),
+    ("{hours}", Unit::Hour12),
+    ("{hours:pad2}", Unit::Hour12Pad),
+    ("{minutes:unpad}", Unit::Minute),
+    ("{minutes}", Unit::Minute2),
+    ("{seconds:unpad}", Unit::Second),
+    ("{seconds}", Unit::Second2),
+    ("{milliseconds}", Unit::Millis),
+    ("{period}", Unit::Period),
+    ("{utc-offset}", Unit::OffsetColon),
+    ("{strftime:%z}", Unit::Offset),
+    ("{timezone}", Unit::ZoneAbb),
+    ("{timezone-full}", Unit::ZoneId),
+    ("{timezone-long}", Unit::ZoneLong),
+    ("{day-of-year}", Unit::DayOfYear),
+    ("{day-of-year:pad3}", Unit::DayOfYear3),
+    ("{iso-week}", Unit::IsoWeek),
+    ("{iso-week:pad2}", Unit::IsoWeek2),
+    ("{iso-week-year}", Unit::IsoWeekYear),
+    ("{quarter}", Unit::Quarter),
+    ("{unix}", Unit::EpochSeconds),
+    ("{unix-ms}", Unit::EpochMillis),
+    ("{time24}", Unit::Clock24),
+    ("{time}", Unit::Clock12),
+];
+
+const MOMENT: &[(&str, Unit)] = &[
+    ("YYYY", Unit::Year),
+    ("YY", Unit::YearShort),
+    ("MMMM", Unit::Month),
+    ("MMM", Unit::MonthAbb),
+    ("MM", Unit::MonthNum2),
+    ("M", Unit::MonthNum),
+    ("DDDD", Unit::DayOfYear3),
+    ("DDD", Unit::DayOfYear),
+    ("DD", Unit::Day2),
+    ("Do", Unit::DayOrdinal),
+    ("D", Unit::Day),
+    ("dddd", Unit::Weekday),
+    ("ddd", Unit::WeekdayAbb),
+    ("HH", Unit::Hour24Pad),
+    ("H", Unit::Hour24),
+    ("hh", Unit::Hour12Pad),
+    ("h", Unit::Hour12),
+    ("mm", Unit::Minute2),
+    ("m", Unit::Minute),
+    ("ss", Unit::Second2),
+    ("s", Unit::Second),
+    ("SSS", Unit::Millis),
+    ("A", Unit::Period),
+    ("Z", Unit::OffsetColon),
+    ("ZZ", Unit::Offset),
+    ("z", Unit::ZoneAbb),
+    ("WW", Unit::IsoWeek2),
+    ("W", Unit::IsoWeek),
+    ("GGGG", Unit::IsoWeekYear),
+    ("Q", Unit::Quarter),
+    ("X", Unit::EpochSeconds),
+    ("x", Unit::EpochMillis),
+];
+
+/// moment tokens with no counterpart; other letters are literal in moment.
+const MOMENT_UNSUPPORTED: &[&str] = &[
+    "gggg", "GG", "gg", "SSSS", "SS", "S", "NNNN", "NNN", "NN", "N", "kk", "k", "ww", "w", "Wo", "wo", "Mo", "Qo", "DDDo", "do", "dd", "d", "a", "zz", "e", "E",
+];
+
+const JAVA: &[(&str, Unit)] = &[
+    ("yyyy", Unit::Year),
+    ("uuuu", Unit::Year),
+    ("yy", Unit::YearShort),
+    ("uu", Unit::YearShort),
+    ("MMMM", Unit::Month),
+    ("MMM", Unit::MonthAbb),
+    ("MM", Unit::MonthNum2),
+    ("M", Unit::MonthNum),
+    ("dd", Unit::Day2),
+    ("d", Unit::Day),
+    ("EEEE", Unit::Weekday),
+    ("EEE", Unit::WeekdayAbb),
+    ("EE", Unit::WeekdayAbb),
+    ("E", Unit::WeekdayAbb),
+    ("HH", Unit::Hour24Pad),
+    ("H", Unit::Hour24),
+    ("hh", Unit::Hour12Pad),
+    ("h", Unit::Hour12),
+    ("mm", Unit::Minute2),
+    ("m", Unit::Minute),
+    ("ss", Unit::Second2),
+    ("s", Unit::Second),
+    ("SSS", Unit::Millis),
+    ("a", Unit::Period),
+    ("xxx", Unit::OffsetColon),
+    ("xx", Unit::Offset),
+    ("Z", Unit::Offset),
+    ("zzzz", Unit::ZoneLong),
+    ("z", Unit::ZoneAbb),
+    ("zz", Unit::ZoneAbb),
+    ("zzz", Unit::ZoneAbb),
+    ("VV", Unit::ZoneId),
+    ("DDD", Unit::DayOfYear3),
+    ("Q", Unit::Quarter),
+];
+
+pub(crate) const DOTNET: &[(&str, Unit)] = &[
+    ("yyyy", Unit::Year),
+    ("yy", Unit::YearShort),
+    ("MMMM", Unit::Month),
+    ("MMM", Unit::MonthAbb),
+    ("MM", Unit::MonthNum2),
+    ("M", Unit::MonthNum),
+    ("dddd", Unit::Weekday),
+    ("ddd", Unit::WeekdayAbb),
+    ("dd", Unit::Day2),
+    ("d", Unit::Day),
+    ("HH", Unit::Hour24Pad),
+    ("H", Unit::Hour24),
+    ("hh", Unit::Hour12Pad),
+    ("h", Unit::Hour12),
+    ("mm", Unit::Minute2),
+    ("m", Unit::Minute),
+    ("ss", Unit::Second2),
+    ("s", Unit::Second),
+    ("fff", Unit::Millis),
+    ("tt", Unit::Period),
+    ("zzz", Unit::OffsetColon),
+];
+
+/// .NET specifier letters; other letters are copied literally.
+const DOTNET_LETTERS: &str = "dfFghHKmMstyz";
+
+pub(crate) const STRFTIME: &[(&str, Unit)] = &[
+    ("%Y", Unit::Year),
+    ("%y", Unit::YearShort),
+    ("%B", Unit::Month),
+    ("%b", Unit::MonthAbb),
+    ("%h", Unit::MonthAbb),
+    ("%m", Unit::MonthNum2),
+    ("%-m", Unit::MonthNum),
+    ("%d", Unit::Day2),
+    ("%-d", Unit::Day),
+    ("%e", Unit::DaySpace),
+    ("%A", Unit::Weekday),
+    ("%a", Unit::WeekdayAbb),
+    ("%H", Unit::Hour24Pad),
+    ("%-H", Unit::Hour24),
+    ("%I", Unit::Hour12Pad),
+    ("%-I", Unit::Hour12),
+    ("%M", Unit::Minute2),
+    ("%-M", Unit::Minute),
+    ("%S", Unit::Second2),
+    ("%-S", Unit::Second),
+    ("%p", Unit::Period),
+    ("%:z", Unit::OffsetColon),
+    ("%z", Unit::Offset),
+    ("%Z", Unit::ZoneAbb),
+    ("%j", Unit::DayOfYear3),
+    ("%-j", Unit::DayOfYear),
+    ("%V", Unit::IsoWeek2),
+    ("%-V", Unit::IsoWeek),
+    ("%G", Unit::IsoWeekYear),
+    ("%s", Unit::EpochSeconds),
+];
+
+/// Shorthand specifiers, expanded as they are read.
+const STRFTIME_SHORTHANDS: &[(&str, &str)] = &[("%F", "%Y-%m-%d"), ("%T", "%H:%M:%S"), ("%R", "%H:%M"), ("%D", "%m/%d/%y")];
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Piece {
+    Literal(String),
+    /// A field with the text it was read from, for reporting.
+    Field(Unit, String),
+    /// Source text with no neutral equivalent.
+    Unknown(String, String),
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Untranslatable {
+    /// The source text that could not be carried over.
+    pub text: String,
+    pub reason: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvertedFormat {
+    pub pattern: String,
+    pub untranslatable: Vec<Untranslatable>,
+}
+
+/// Translate `pattern` from one dialect to another, e.g. moment's
+/// `YYYY-MM-DD HH:mm` to `{year}-{month-num-pad}-{date:pad2} {time24}`.
+#[tauri::command]
+pub fn convert_format(pattern: String, from: Dialect, to: Dialect) -> ConvertedFormat {
+    convert(&pattern, from, to)
+}
+
+pub fn convert(pattern: &str, from: Dialect, to: Dialect) -> ConvertedFormat {
+    let (mut pieces, invalid) = read(pattern, from);
+    if to == Dialect::Thymestamp {
+        pieces = merge_clocks(pieces);
+    }
+    let mut converted = write_with(&pieces, from, to.label(), |unit| lookup(to.table(), unit), |s| escape(s, to));
+    converted.untranslatable.splice(0..0, invalid);
+    converted
+}
+
+/// Fold an hour, a lone ":" and padded minutes into the clock field
+/// ThymeStamp writes as `{time24}` or `{time}`. Only whole fields are
+/// merged, so literal text that happens to look like tokens is left alone.
+fn merge_clocks(pieces: Vec<Piece>) -> Vec<Piece> {
+    let mut out: Vec<Piece> = Vec::with_capacity(pieces.len());
+    for piece in pieces {
+        if let Piece::Field(Unit::Minute2, minutes) = &piece {
+            if let [.., Piece::Field(hour @ (Unit::Hour24Pad | Unit::Hour12), hours), Piece::Literal(colon)] = out.as_slice() {
+                if colon == ":" {
+                    let clock = if *hour == Unit::Hour24Pad { Unit::Clock24 } else { Unit::Clock12 };
+                    let source = format!("{}:{}", hours, minutes);
+                    out.truncate(out.len() - 2);
+                    out.push(Piece::Field(clock, source));
+                    continue;
+                }
+            }
+        }
+        out.push(piece);
+    }
+    out
+}
+
+/// Read `pattern` into pieces. For ThymeStamp, text with diagnostics is kept
+/// as a literal piece and also returned as untranslatable.
+pub(crate) fn read(pattern: &str, from: Dialect) -> (Vec<Piece>, Vec<Untranslatable>) {
+    let pieces = match from {
+        Dialect::Thymestamp => read_thymestamp(pattern),
+        Dialect::Moment => read_symbols(pattern, MOMENT, Quoting::Brackets),
+        Dialect::Java => read_symbols(pattern, JAVA, Quoting::Java),
+        Dialect::Dotnet => read_symbols(pattern, DOTNET, Quoting::Dotnet),
+        Dialect::Strftime => read_strftime(pattern),
+    };
+    let mut invalid = Vec::new();
+    if from == Dialect::Thymestamp {
+        let utf16: Vec<u16> = pattern.encode_utf16().collect();
+        invalid.extend(Template::parse(pattern).diagnostics.into_iter().map(|d| Untranslatable {
+            text: String::from_utf16_lossy(&utf16[d.start..d.end]),
+            reason: d.message,
+        }));
+    }
+    (pieces, invalid)
+}
+
+pub(crate) fn lookup(table: &[(&'static str, Unit)], unit: Unit) -> Option<&'static str> {
+    table.iter().find(|(_, u)| *u == unit).map(|(sym, _)| *sym)
+}
+
+fn read_thymestamp(pattern: &str) -> Vec<Piece> {
+    let template = Template::parse(pattern);
+    let mut pieces = Vec::new();
+    for node in &template.nodes {
+        match node {
+            Node::Literal(s) => pieces.push(Piece::Literal(s.clone())),
+            Node::Strftime { pattern, zone: None, .. } => pieces.extend(read_strftime(pattern)),
+            Node::Token(p) if p.zone.is_none() && p.modifiers.is_empty() && matches!(p.token, Token::Time | Token::Time24) => {
+                let (hour, text) = if p.token == Token::Time { (Unit::Hour12, "{hours}") } else { (Unit::Hour24Pad, "{hours24:pad2}") };
+                pieces.push(Piece::Field(hour, text.into()));
+                pieces.push(Piece::Literal(":".into()));
+                pieces.push(Piece::Field(Unit::Minute2, "{minutes}".into()));
+            }
+            Node::Token(p) => {
+                let text = p.to_string();
+                let unit = THYMESTAMP.iter().find(|(sym, _)| {
+                    Template::parse(sym).nodes.first() == Some(&Node::Token(p.clone()))
+                });
+                pieces.push(match unit {
+                    Some((_, unit)) => Piece::Field(*unit, text),
+                    None if p.zone.is_some() => Piece::Unknown(text, "per-token timezones have no equivalent".into()),
+                    None if p.calendar.is_some() => Piece::Unknown(text, "alternative calendars have no equivalent".into()),
+                    None => Piece::Unknown(text, "no equivalent field".into()),
+                });
+            }
+            Node::Strftime { pattern, .. } => {
+                pieces.push(Piece::Unknown(format!("{{strftime:{}}}", pattern), "zone scopes have no equivalent".into()));
+            }
+        }
+    }
+    pieces
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Quoting {
+    /// moment: `[literal]`.
+    Brackets,
+    /// Java: `'literal'`, with `''` for a quote.
+    Java,
+    /// .NET: `'literal'`, `"literal"` or `\c`.
+    Dotnet,
+}
+
+/// Read a letter-symbol dialect. moment tokens mix letters ("Do", "Wo"), so
+/// they are matched longest-first; Java and .NET repeat one letter to set the
+/// width, so each whole run of a letter is looked up by its length.
+fn read_symbols(pattern: &str, table: &[(&str, Unit)], quoting: Quoting) -> Vec<Piece> {
+    let mut pieces = Vec::new();
+    let mut rest = pattern;
+    while let Some(c) = rest.chars().next() {
+        // Quoted literals.
+        let quoted = match (quoting, c) {
+            (Quoting::Brackets, '[') => rest[1..].find(']').map(|end| (rest[1..=end].to_string(), end + 2)),
+            (Quoting::Java, '\'') => java_quoted(rest),
+            (Quoting::Dotnet, '\'' | '"') => rest[1..].find(c).map(|end| (rest[1..=end].to_string(), end + 2)),
+            (Quoting::Dotnet, '\\') => rest[1..].chars().next().map(|e| (e.to_string(), 1 + e.len_utf8())),
+            _ => None,
+        };
+        if let Some((text, len)) = quoted {
+            push_literal(&mut pieces, &text);
+            rest = &rest[len..];
+            continue;
+        }
+        if !c.is_ascii_alphabetic() {
+            push_literal(&mut pieces, c.encode_utf8(&mut [0; 4]));
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+        let (sym, piece) = match quoting {
+            Quoting::Brackets => read_moment_token(rest, table),
+            _ => {
+                let sym = &rest[..rest.chars().take_while(|x| *x == c).count()];
+                let known = table.iter().find(|(s, _)| *s == sym).map(|(_, unit)| Piece::Field(*unit, sym.to_string()));
+                // Java reserves every letter; .NET copies non-specifiers through.
+                let reserved = quoting == Quoting::Java || DOTNET_LETTERS.contains(c);
+                let reason = if table.iter().any(|(s, _)| s.starts_with(c)) {
+                    format!("no equivalent for {} repeated {} times", c, sym.len())
+                } else {
+                    "unsupported pattern letter".to_string()
+                };
+                (sym, known.or_else(|| reserved.then(|| Piece::Unknown(sym.to_string(), reason))))
+            }
+        };
+        match piece {
+            Some(piece) => pieces.push(piece),
+            None => push_literal(&mut pieces, sym),
+        }
+        rest = &rest[sym.len()..];
+    }
+    pieces
+}
+
+/// The moment token at the start of `rest` by longest match, or its first
+/// letter as a literal (`None`).
+fn read_moment_token<'a>(rest: &'a str, table: &[(&'a str, Unit)]) -> (&'a str, Option<Piece>) {
+    let known = table
+        .iter()
+        .filter(|(sym, _)| rest.starts_with(sym))
+        .max_by_key(|(sym, _)| sym.len())
+        .map(|(sym, unit)| (*sym, Some(*unit)));
+    let unsupported = MOMENT_UNSUPPORTED.iter().filter(|sym| rest.starts_with(**sym)).max_by_key(|sym| sym.len()).map(|sym| (*sym, None));
+    match [known, unsupported].into_iter().flatten().max_by_key(|(sym, _)| sym.len()) {
+        Some((sym, Some(unit))) => (sym, Some(Piece::Field(unit, sym.to_string()))),
+        Some((sym, None)) => (sym, Some(Piece::Unknown(sym.to_string(), "no equivalent field".into()))),
+        None => (&rest[..1], None),
+    }
+}
+
+/// A Java quoted literal at the start of `s`, with its length in the source.
+fn java_quoted(s: &str) -> Option<(String, usize)> {
+    if s[1..].starts_with('\'') {
+        return Some(("'".to_string(), 2));
+    }
+    let mut text = String::new();
+    let mut chars = s.char_indices().skip(1).peekable();
+    while let Some((i, c)) = chars.next() {
+        if c != '\'' {
+            text.push(c);
+        } else if chars.next_if(|(_, next)| *next == '\'').is_some() {
+            text.push('\'');
+        } else {
+            return Some((text, i + 1));
+        }
+    }
+    None
+}
+
+fn read_strftime(pattern: &str) -> Vec<Piece> {
+    let mut pieces = Vec::new();
+    let mut rest = pattern;
+    while let Some(i) = rest.find('%') {
+        push_literal(&mut pieces, &rest[..i]);
+        rest = &rest[i..];
+        if let Some(after) = rest.strip_prefix("%%") {
+            push_literal(&mut pieces, "%");
+            rest = after;
+            continue;
+        }
+        if let Some((short, long)) = STRFTIME_SHORTHANDS.iter().find(|(short, _)| rest.starts_with(short)) {
+            for piece in read_strftime(long) {
+                match piece {
+                    Piece::Literal(text) => push_literal(&mut pieces, &text),
+                    piece => pieces.push(piece),
+                }
+            }
+            rest = &rest[short.len()..];
+            continue;
+        }
+        match STRFTIME.iter().filter(|(sym, _)| rest.starts_with(sym)).max_by_key(|(sym, _)| sym.len()) {
+            Some((sym, unit)) => {
+                pieces.push(Piece::Field(*unit, sym.to_string()));
+                rest = &rest[sym.len()..];
+            }
+            None => {
+                // `%` plus an optional flag and one specifier character.
+                let flag = usize::from(rest[1..].starts_with(['-', '_', '0', '^', '#', ':']));
+                let len = rest[1 + flag..].chars().next().map_or(rest.len(), |c| 1 + flag + c.len_utf8());
+                pieces.push(Piece::Unknown(rest[..len].to_string(), "no equivalent field".into()));
+                rest = &rest[len..];
+            }
+        }
+    }
+    push_literal(&mut pieces, rest);
+    pieces
+}
+
+fn push_literal(pieces: &mut Vec<Piece>, s: &str) {
+    if s.is_empty() {
+        return;
+    }
+    if let Some(Piece::Literal(prev)) = pieces.last_mut() {
+        prev.push_str(s);
+    } else {
+        pieces.push(Piece::Literal(s.to_string()));
+    }
+}
+
+/// Write pieces using `symbol` for fields and `escape` for literal text.
+pub(crate) fn write_with(
+    pieces: &[Piece],
+    from: Dialect,
+    to: &str,
+    symbol: impl Fn(Unit) -> Option<&'static str>,
+    escape: impl Fn(&str) -> String,
+) -> ConvertedFormat {
+    let mut pattern = String::new();
+    let mut untranslatable = Vec::new();
+    let mut literal = String::new();
+    for piece in pieces {
+        match piece {
+            Piece::Literal(s) => literal.push_str(s),
+            Piece::Field(unit, source) => match symbol(*unit) {
+                Some(sym) => {
+                    pattern.push_str(&escape(&std::mem::take(&mut literal)));
+                    pattern.push_str(sym);
+                }
+                None => {
+                    untranslatable.push(Untranslatable {
+                        text: source.clone(),
+                        reason: format!("no equivalent in {}", to),
+                    });
+                    literal.push_str(source);
+                }
+            },
+            Piece::Unknown(source, reason) => {
+                untranslatable.push(Untranslatable { text: source.clone(), reason: format!("{} ({})", reason, from.label()) });
+                literal.push_str(source);
+            }
+        }
+    }
+    pattern.push_str(&escape(&literal));
+    ConvertedFormat { pattern, untranslatable }
+}
+
+/// Quote literal text so the target dialect doesn't read it as fields.
+pub(crate) fn escape(s: &str, to: Dialect) -> String {
+    if s.is_empty() {
+        return String::new();
+    }
+    match to {
+        Dialect::Thymestamp => s.replace('{', "{{").replace('}', "}}"),
+        Dialect::Strftime => s.replace('%', "%%"),
+        Dialect::Moment => quote_runs(s, |c| c.is_ascii_alphabetic() || c == '[' || c == ']', |run| format!("[{}]", run)),
+        Dialect::Java => quote_runs(s, |c| c.is_ascii_alphabetic() || c == '\'', |run| format!("'{}'", run.replace('\'', "''"))),
+        Dialect::Dotnet => quote_runs(s, |c| c.is_ascii_alphabetic() || "\\'\"%:/".contains(c), |run| {
+            run.chars().flat_map(|c| ['\\', c]).collect()
+        }),
+    }
+}
+
+/// Quote maximal runs of characters matching `special`, leaving the rest.
+pub(crate) fn quote_runs(s: &str, special: impl Fn(char) -> bool, quote: impl Fn(&str) -> String) -> String {
+    let mut out = String::new();
+    let mut run = String::new();
+    for c in s.chars() {
+        if special(c) {
+            run.push(c);
+        } else {
+            if !run.is_empty() {
+                out.push_str(&quote(&std::mem::take(&mut run)));
+            }
+            out.push(c);
+        }
+    }
+    if !run.is_empty() {
+        out.push_str(&quote(&run));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn untranslatable(c: &ConvertedFormat) -> Vec<&str> {
+        c.untranslatable.iter().map(|u| u.text.as_str()).collect()
+    }
+
+    #[test]
+    fn letter_runs_are_read_whole() {
+        let c = convert("yyyy-MM-dd'T'HH:mm:ss.SSSSSS zzz", Dialect::Java, Dialect::Thymestamp);
+        assert_eq!(c.pattern, "{year}-{month-num-pad}-{date:pad2}T{time24}:{seconds}.SSSSSS {timezone}");
+        assert_eq!(untranslatable(&c), ["SSSSSS"]);
+        for zone in ["z", "zz", "zzz"] {
+            assert_eq!(convert(zone, Dialect::Java, Dialect::Thymestamp).pattern, "{timezone}");
+        }
+        assert_eq!(convert("zzzz", Dialect::Java, Dialect::Thymestamp).pattern, "{timezone-long}");
+        let c = convert("HH:mm:ss.ffffff zzz", Dialect::Dotnet, Dialect::Thymestamp);
+        assert_eq!(c.pattern, "{time24}:{seconds}.ffffff {utc-offset}");
+        assert_eq!(untranslatable(&c), ["ffffff"]);
+        let c = convert("dddd 'at' h:mm tt", Dialect::Dotnet, Dialect::Java);
+        assert_eq!((c.pattern.as_str(), c.untranslatable.len()), ("EEEE 'at' h:mm a", 0));
+    }
+
+    #[test]
+    fn clocks_merge_only_whole_fields() {
+        assert_eq!(convert("HH:mm", Dialect::Moment, Dialect::Thymestamp).pattern, "{time24}");
+        assert_eq!(convert("h:mm A", Dialect::Moment, Dialect::Thymestamp).pattern, "{time} {period}");
+        // Padded 12-hour clocks and other separators have no single token.
+        assert_eq!(convert("hh:mm", Dialect::Moment, Dialect::Thymestamp).pattern, "{hours:pad2}:{minutes}");
+        assert_eq!(convert("HH.mm", Dialect::Moment, Dialect::Thymestamp).pattern, "{hours24:pad2}.{minutes}");
+        // Literal text spelling out tokens stays escaped, not rewritten.
+        let c = convert("{{hours}}:{{minutes}}", Dialect::Thymestamp, Dialect::Thymestamp);
+        assert_eq!(c.pattern, "{{hours}}:{{minutes}}");
+        assert_eq!(convert("{time24}", Dialect::Thymestamp, Dialect::Java).pattern, "HH:mm");
+    }
+
+    #[test]
+    fn strftime_shorthands_respect_escapes() {
+        let c = convert("%%F %F", Dialect::Strftime, Dialect::Thymestamp);
+        assert_eq!(c.pattern, "%F {year}-{month-num-pad}-{date:pad2}");
+        assert_eq!(convert("%T", Dialect::Strftime, Dialect::Moment).pattern, "HH:mm:ss");
+    }
+}
@@ -0,0 +1,267 @@
+//! Machine timestamp encodings: Unix at several precisions, and the epochs
+//! used by Windows, .NET, Apple, NTP, GPS and spreadsheets.
+//!
+//! Each encoding is rendered from one captured instant and parses back to
+//! it (to the encoding's precision).
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Epoch {
+    #[serde(rename = "unix")]
+    UnixSeconds,
+    #[serde(rename = "unix_ms")]
+    UnixMillis,
+    #[serde(rename = "unix_us")]
+    UnixMicros,
+    #[serde(rename = "unix_ns")]
+    UnixNanos,
+    /// Unix seconds in lowercase hex, as in some log and cookie formats.
+    UnixHex,
+    /// Windows FILETIME: 100 ns intervals since 1601, as 16 hex digits.
+    Filetime,
+    /// .NET `DateTime.Ticks`: 100 ns intervals since 0001-01-01.
+    DotnetTicks,
+    /// Seconds since 2001-01-01, as used by `NSDate` and Core Data.
+    Cocoa,
+    /// Active Directory / LDAP: FILETIME as a decimal number.
+    Ldap,
+    /// NTP seconds since 1900 (era 0).
+    Ntp,
+    /// GPS seconds since 1980-01-06, which do not skip leap seconds.
+    Gps,
+    /// Excel / Lotus 1-2-3 serial date: local days since 1899-12-30.
+    Excel,
+}
+
+/// Keyword for each encoding, as accepted by `generate_timestamp`.
+pub const EPOCHS: &[(Epoch, &str)] = &[
+    (Epoch::UnixSeconds, "unix"),
+    (Epoch::UnixMillis, "unix_ms"),
+    (Epoch::UnixMicros, "unix_us"),
+    (Epoch::UnixNanos, "unix_ns"),
+    (Epoch::UnixHex, "unix_hex"),
+    (Epoch::Filetime, "filetime"),
+    (Epoch::DotnetTicks, "dotnet_ticks"),
+    (Epoch::Cocoa, "cocoa"),
+    (Epoch::Ldap, "ldap"),
+    (Epoch::Ntp, "ntp"),
+    (Epoch::Gps, "gps"),
+    (Epoch::Excel, "excel"),
+];
+
+const NANOS_PER_SECOND: i128 = 1_000_000_000;
+/// Seconds from 1601-01-01 to the Unix epoch.
+const FILETIME_OFFSET: i128 = 11_644_473_600;
+/// Seconds from 0001-01-01 to the Unix epoch.
+const TICKS_OFFSET: i128 = 62_135_596_800;
+/// Seconds from the Unix epoch to 2001-01-01.
+const COCOA_OFFSET: i128 = 978_307_200;
+/// Seconds from 1900-01-01 to the Unix epoch.
+const NTP_OFFSET: i128 = 2_208_988_800;
+/// Seconds from the Unix epoch to 1980-01-06.
+const GPS_OFFSET: i128 = 315_964_800;
+
+/// Months (UTC) whose first second follows a leap second, since the GPS epoch.
+/// GPS time runs ahead of UTC by one second for each.
+const LEAP_SECONDS: &[(i32, u32)] = &[
+    (1981, 7), (1982, 7), (1983, 7), (1985, 7), (1988, 1), (1990, 1), (1991, 1), (1992, 7), (1993, 7),
+    (1994, 7), (1996, 1), (1997, 7), (1999, 1), (2006, 1), (2009, 1), (2012, 7), (2015, 7), (2017, 1),
+];
+
+impl Epoch {
+    pub fn from_keyword(keyword: &str) -> Option<Epoch> {
+        EPOCHS.iter().find(|e| e.1 == keyword).map(|e| e.0)
+    }
+
+    /// `at` in this encoding. `local` is the wall time Excel serials use.
+    /// FILETIME is unsigned, so instants before 1601 are an error.
+    pub fn encode(self, at: DateTime<Utc>, local: NaiveDateTime) -> Result<String, String> {
+        let nanos = i128::from(at.timestamp()) * NANOS_PER_SECOND + i128::from(at.timestamp_subsec_nanos());
+        let seconds = i128::from(at.timestamp());
+        let hundred_ns = |offset: i128| (nanos + offset * NANOS_PER_SECOND).div_euclid(100);
+        Ok(match self {
+            Epoch::UnixSeconds => seconds.to_string(),
+            Epoch::UnixMillis => nanos.div_euclid(1_000_000).to_string(),
+            Epoch::UnixMicros => nanos.div_euclid(1_000).to_string(),
+            Epoch::UnixNanos => nanos.to_string(),
+            Epoch::UnixHex if seconds < 0 => format!("-{:x}", -seconds),
+            Epoch::UnixHex => format!("{:x}", seconds),
+            Epoch::Filetime => match hundred_ns(FILETIME_OFFSET) {
+                ticks if ticks < 0 => return Err(format!("FILETIME can't represent {}, before 1601", at.to_rfc3339())),
+                ticks => format!("{:016X}", ticks),
+            },
+            Epoch::DotnetTicks => hundred_ns(TICKS_OFFSET).to_string(),
+            Epoch::Cocoa => (seconds - COCOA_OFFSET).to_string(),
+            Epoch::Ldap => hundred_ns(FILETIME_OFFSET).to_string(),
+            Epoch::Ntp => (seconds + NTP_OFFSET).to_string(),
+            Epoch::Gps => (seconds - GPS_OFFSET + leap_seconds(seconds)).to_string(),
+            Epoch::Excel => excel_serial(local),
+        })
+    }
+
+    /// Read `input` back; `tz` is the zone an Excel serial's wall time is in.
+    pub fn decode(self, input: &str, tz: Tz) -> Option<DateTime<Utc>> {
+        let input = input.trim();
+        let seconds = |offset: i128| from_nanos(decimal(input, NANOS_PER_SECOND)? + offset * NANOS_PER_SECOND);
+        let hundred_ns = |offset: i128| from_nanos(input.parse::<i128>().ok()? * 100 - offset * NANOS_PER_SECOND);
+        match self {
+            Epoch::UnixSeconds => seconds(0),
+            Epoch::UnixMillis => from_nanos(decimal(input, 1_000_000)?),
+            Epoch::UnixMicros => from_nanos(decimal(input, 1_000)?),
+            Epoch::UnixNanos => from_nanos(input.parse().ok()?),
+            Epoch::UnixHex => {
+                let hex = input.strip_prefix("0x").or_else(|| input.strip_prefix("0X")).unwrap_or(input);
+                from_nanos(i128::from_str_radix(hex, 16).ok()? * NANOS_PER_SECOND)
+            }
+            Epoch::Filetime => {
+                let hex: String = input.trim_start_matches("0x").trim_start_matches("0X").split(':').collect();
+                from_nanos(i128::from_str_radix(&hex, 16).ok()? * 100 - FILETIME_OFFSET * NANOS_PER_SECOND)
+            }
+            Epoch::DotnetTicks => hundred_ns(TICKS_OFFSET),
+            Epoch::Cocoa => seconds(COCOA_OFFSET),
+            Epoch::Ldap => hundred_ns(FILETIME_OFFSET),
+            Epoch::Ntp => seconds(-NTP_OFFSET),
+            Epoch::Gps => {
+                let gps = seconds(GPS_OFFSET)?;
+                // Step back over the leap seconds GPS has counted by then.
+                let unix = i128::from(gps.timestamp());
+                let leaps = (0..=LEAP_SECONDS.len() as i128).find(|&n| leap_seconds(unix - n) == n)?;
+                from_nanos((unix - leaps) * NANOS_PER_SECOND + i128::from(gps.timestamp_subsec_nanos()))
+            }
+            Epoch::Excel => {
+                let nanos = decimal(input, 86_400 * NANOS_PER_SECOND)?;
+                // Serials carry about a millisecond of precision.
+                let millis = (nanos + 500_000).div_euclid(1_000_000);
+                let local = excel_base() + chrono::Duration::milliseconds(i64::try_from(millis).ok()?);
+                crate::parse::local_to_utc(local, tz)
+            }
+        }
+    }
+}
+
+fn leap_seconds(unix: i128) -> i128 {
+    LEAP_SECONDS
+        .iter()
+        .filter_map(|&(y, m)| NaiveDate::from_ymd_opt(y, m, 1))
+        .filter(|d| i128::from(d.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc().timestamp()) <= unix)
+        .count() as i128
+}
+
+fn excel_base() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(1899, 12, 30).and_then(|d| d.and_hms_opt(0, 0, 0)).unwrap_or_default()
+}
+
+/// Days and fraction of a day, to 8 decimal places (under a millisecond).
+fn excel_serial(local: NaiveDateTime) -> String {
+    let millis = (local - excel_base()).num_milliseconds();
+    let (days, rest) = (millis.div_euclid(86_400_000), millis.rem_euclid(86_400_000));
+    let fraction = format!("{:08}", (i128::from(rest) * 100_000_000 + 43_200_000) / 86_400_000);
+    match fraction.trim_end_matches('0') {
+        "" => days.to_string(),
+        digits => format!("{}.{}", days, digits),
+    }
+}
+
+/// A decimal number such as `-12.5` times `scale`, without float rounding.
+fn decimal(s: &str, scale: i128) -> Option<i128> {
+    let (negative, digits) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+    let (whole, frac) = digits.split_once('.').unwrap_or((digits, ""));
+    if whole.is_empty() || !whole.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit()) || frac.len() > 18 {
+        return None;
+    }
+    let mut value = whole.parse::<i128>().ok()?.checked_mul(scale)?;
+    if !frac.is_empty() {
+        value += frac.parse::<i128>().ok()? * scale / 10i128.pow(frac.len() as u32);
+    }
+    Some(if negative { -value } else { value })
+}
+
+fn from_nanos(nanos: i128) -> Option<DateTime<Utc>> {
+    let seconds = i64::try_from(nanos.div_euclid(NANOS_PER_SECOND)).ok()?;
+    DateTime::from_timestamp(seconds, nanos.rem_euclid(NANOS_PER_SECOND) as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{SubsecRound, TimeZone};
+
+    fn encode(epoch: Epoch, at: DateTime<Utc>) -> Result<String, String> {
+        epoch.encode(at, at.naive_utc())
+    }
+
+    #[test]
+    fn known_values() {
+        let at = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        let cases = [
+            (Epoch::UnixSeconds, "1700000000"),
+            (Epoch::UnixMillis, "1700000000000"),
+            (Epoch::UnixMicros, "1700000000000000"),
+            (Epoch::UnixNanos, "1700000000000000000"),
+            (Epoch::UnixHex, "6553f100"),
+            (Epoch::Filetime, "01DA1747C66D0000"),
+            (Epoch::DotnetTicks, "638355968000000000"),
+            (Epoch::Cocoa, "721692800"),
+            (Epoch::Ldap, "133444736000000000"),
+            (Epoch::Ntp, "3908988800"),
+            (Epoch::Gps, "1384035218"),
+            (Epoch::Excel, "45244.92592593"),
+        ];
+        for (epoch, expected) in cases {
+            assert_eq!(encode(epoch, at).unwrap(), expected, "{:?}", epoch);
+        }
+    }
+
+    #[test]
+    fn every_encoding_round_trips_to_its_precision() {
+        let at = Utc.timestamp_opt(1_700_000_000, 123_456_789).unwrap();
+        let hundred_ns = Utc.timestamp_opt(1_700_000_000, 123_456_700).unwrap();
+        for &(epoch, keyword) in EPOCHS {
+            let expected = match epoch {
+                Epoch::UnixMillis | Epoch::Excel => at.trunc_subsecs(3),
+                Epoch::UnixMicros => at.trunc_subsecs(6),
+                Epoch::UnixNanos => at,
+                Epoch::Filetime | Epoch::DotnetTicks | Epoch::Ldap => hundred_ns,
+                _ => at.trunc_subsecs(0),
+            };
+            let berlin = at.with_timezone(&chrono_tz::Europe::Berlin);
+            let encoded = epoch.encode(at, berlin.naive_local()).unwrap();
+            assert_eq!(epoch.decode(&encoded, chrono_tz::Europe::Berlin), Some(expected), "{} {}", keyword, encoded);
+        }
+    }
+
+    #[test]
+    fn pre_epoch_instants() {
+        let before_1970 = Utc.timestamp_opt(-1, 0).unwrap();
+        assert_eq!(encode(Epoch::UnixHex, before_1970).unwrap(), "-1");
+        assert_eq!(Epoch::UnixHex.decode("-1", Tz::UTC), Some(before_1970));
+        let first = Utc.with_ymd_and_hms(1601, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(encode(Epoch::Filetime, first).unwrap(), "0000000000000000");
+        assert!(encode(Epoch::Filetime, first - chrono::Duration::nanoseconds(100)).is_err());
+        assert!(encode(Epoch::Filetime, Utc.with_ymd_and_hms(1500, 6, 1, 0, 0, 0).unwrap()).is_err());
+    }
+
+    #[test]
+    fn gps_counts_leap_seconds() {
+        let before = Utc.with_ymd_and_hms(2016, 12, 31, 23, 59, 59).unwrap();
+        let after = Utc.with_ymd_and_hms(2017, 1, 1, 0, 0, 0).unwrap();
+        let (b, a) = (encode(Epoch::Gps, before).unwrap(), encode(Epoch::Gps, after).unwrap());
+        assert_eq!(a.parse::<i64>().unwrap() - b.parse::<i64>().unwrap(), 2);
+        assert_eq!(Epoch::Gps.decode(&a, Tz::UTC), Some(after));
+    }
+
+    #[test]
+    fn malformed_input_is_rejected() {
+        assert_eq!(Epoch::UnixSeconds.decode("12x", Tz::UTC), None);
+        assert_eq!(Epoch::UnixSeconds.decode(".5", Tz::UTC), None);
+        assert_eq!(Epoch::Filetime.decode("not hex", Tz::UTC), None);
+        assert_eq!(Epoch::Excel.decode("", Tz::UTC), None);
+    }
+}
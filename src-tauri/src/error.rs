@@ -0,0 +1,82 @@
+//! Typed errors returned by the timestamp commands.
+//!
+//! Serialized with a `kind` tag so the webview can branch on the variant,
+//! e.g. `{ "kind": "unknownTimezone", "name": "Europe/Pari", "suggestions": ["Europe/Paris"] }`.
+
+use serde::Serialize;
+
+use crate::template::Diagnostic;
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum TimestampError {
+    #[serde(rename_all = "camelCase")]
+    UnknownTimezone { name: String, suggestions: Vec<String> },
+    /// The format string has unknown tokens, invalid modifiers or unbalanced braces.
+    #[serde(rename_all = "camelCase")]
+    InvalidFormat { format: String, diagnostics: Vec<Diagnostic> },
+    #[serde(rename_all = "camelCase")]
+    InvalidInstant { message: String },
+    /// A per-call setting such as `fiscal` is out of range.
+    #[serde(rename_all = "camelCase")]
+    InvalidSetting { name: String, message: String },
+    /// The system clipboard refused the write.
+    #[serde(rename_all = "camelCase")]
+    Clipboard { message: String },
+}
+
+impl std::fmt::Display for TimestampError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimestampError::UnknownTimezone { name, suggestions } if !suggestions.is_empty() => {
+                write!(f, "Unknown timezone '{}' (did you mean {}?)", name, suggestions.join(", "))
+            }
+            TimestampError::UnknownTimezone { name, .. } => write!(f, "Unknown timezone '{}'", name),
+            TimestampError::InvalidFormat { diagnostics, .. } => {
+                let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+                write!(f, "Invalid format: {}", messages.join("; "))
+            }
+            TimestampError::InvalidInstant { message } => f.write_str(message),
+            TimestampError::InvalidSetting { name, message } => write!(f, "Invalid {}: {}", name, message),
+            TimestampError::Clipboard { message } => write!(f, "Failed to copy to clipboard: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for TimestampError {}
+
+/// Up to `limit` candidates closest to `input` by edit distance, ignoring case.
+/// Candidates containing `/` also match on their last segment, so "tokyo"
+/// suggests "Asia/Tokyo".
+pub fn suggestions<'a>(input: &str, candidates: impl IntoIterator<Item = &'a str>, limit: usize) -> Vec<String> {
+    let input = input.trim().to_lowercase();
+    if input.is_empty() {
+        return Vec::new();
+    }
+    let max_distance = (input.chars().count() / 3).max(2);
+    let mut scored: Vec<(usize, &str)> = candidates
+        .into_iter()
+        .filter_map(|c| {
+            let lower = c.to_lowercase();
+            let tail = lower.rsplit('/').next().unwrap_or(&lower);
+            let d = edit_distance(&input, &lower).min(edit_distance(&input, tail));
+            (d <= max_distance).then_some((d, c))
+        })
+        .collect();
+    scored.sort();
+    scored.into_iter().take(limit).map(|(_, c)| c.to_string()).collect()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
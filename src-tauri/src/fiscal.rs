@@ -0,0 +1,192 @@
+//! Fiscal calendars for `{fiscal-year}`, `{fiscal-quarter}`,
+//! `{fiscal-period}` and `{fiscal-week}`.
+//!
+//! A fiscal year is named after the calendar year it ends in, so with an
+//! October start, 2025-11-03 is in fiscal 2026.
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// The `fiscal` preference, also accepted per call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FiscalCalendar {
+    /// Month the fiscal year starts in, 1–12.
+    pub start_month: u32,
+    /// Week-based retail calendar. Without one, periods are calendar months.
+    pub retail: Option<RetailPattern>,
+}
+
+impl Default for FiscalCalendar {
+    fn default() -> Self {
+        FiscalCalendar { start_month: 1, retail: None }
+    }
+}
+
+/// Weeks per period within each quarter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RetailPattern {
+    #[serde(rename = "4-4-5")]
+    FourFourFive,
+    #[serde(rename = "4-5-4")]
+    FourFiveFour,
+    #[serde(rename = "5-4-4")]
+    FiveFourFour,
+}
+
+impl RetailPattern {
+    fn weeks(self) -> [u32; 3] {
+        match self {
+            RetailPattern::FourFourFive => [4, 4, 5],
+            RetailPattern::FourFiveFour => [4, 5, 4],
+            RetailPattern::FiveFourFour => [5, 4, 4],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FiscalDate {
+    pub year: i32,
+    pub quarter: u32,
+    /// 1–12: the fiscal month, or the retail period.
+    pub period: u32,
+    /// 1–53, counted from the first day of the fiscal year.
+    pub week: u32,
+}
+
+impl FiscalCalendar {
+    pub fn validate(&self) -> Result<(), String> {
+        if (1..=12).contains(&self.start_month) {
+            Ok(())
+        } else {
+            Err(format!("Fiscal start month must be 1-12, got {}", self.start_month))
+        }
+    }
+
+    /// Where `date` falls in the fiscal calendar. Retail years begin on
+    /// `first_day` nearest the 1st of the start month and run 52 or 53 weeks.
+    pub fn locate(&self, date: NaiveDate, first_day: Weekday) -> FiscalDate {
+        let year_start = |y: i32| match self.retail {
+            Some(_) => retail_start(y, self.start_month, first_day),
+            None => month_start(y, self.start_month),
+        };
+        let mut start_year = date.year();
+        if date < year_start(start_year) {
+            start_year -= 1;
+        } else if date >= year_start(start_year + 1) {
+            start_year += 1;
+        }
+        let start = year_start(start_year);
+        let day0 = (date - start).num_days() as u32;
+        let week = day0 / 7 + 1;
+        let period = match self.retail {
+            Some(pattern) => retail_period(week, pattern),
+            None => (date.month() + 12 - self.start_month) % 12 + 1,
+        };
+        FiscalDate {
+            year: start_year + i32::from(self.start_month != 1),
+            quarter: (period - 1) / 3 + 1,
+            period,
+            week,
+        }
+    }
+}
+
+fn month_start(year: i32, month: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, 1).unwrap_or(NaiveDate::MIN)
+}
+
+/// The `first_day` weekday closest to the 1st of `month`.
+fn retail_start(year: i32, month: u32, first_day: Weekday) -> NaiveDate {
+    let first = month_start(year, month);
+    let back = (7 + first.weekday().num_days_from_monday() - first_day.num_days_from_monday()) % 7;
+    let start = first - chrono::Duration::days(i64::from(back));
+    if back > 3 {
+        start + chrono::Duration::days(7)
+    } else {
+        start
+    }
+}
+
+/// Period for a 1-based week; a 53rd week belongs to the last period.
+fn retail_period(week: u32, pattern: RetailPattern) -> u32 {
+    let mut end = 0;
+    for period in 1..=12 {
+        end += pattern.weeks()[((period - 1) % 3) as usize];
+        if week <= end {
+            return period;
+        }
+    }
+    12
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn fiscal(start_month: u32, retail: Option<RetailPattern>) -> FiscalCalendar {
+        FiscalCalendar { start_month, retail }
+    }
+
+    #[test]
+    fn years_are_named_for_the_year_they_end_in() {
+        let october = fiscal(10, None);
+        let at = |d| october.locate(d, Weekday::Mon);
+        assert_eq!(at(date(2025, 11, 3)), FiscalDate { year: 2026, quarter: 1, period: 2, week: 5 });
+        assert_eq!(at(date(2025, 10, 1)).year, 2026);
+        assert_eq!(at(date(2025, 9, 30)), FiscalDate { year: 2025, quarter: 4, period: 12, week: 53 });
+        let calendar = fiscal(1, None).locate(date(2025, 5, 10), Weekday::Mon);
+        assert_eq!((calendar.year, calendar.quarter, calendar.period), (2025, 2, 5));
+    }
+
+    #[test]
+    fn quarter_boundaries() {
+        let october = fiscal(10, None);
+        let at = |d| october.locate(d, Weekday::Mon);
+        assert_eq!((at(date(2025, 12, 31)).quarter, at(date(2026, 1, 1)).quarter), (1, 2));
+        assert_eq!((at(date(2026, 3, 31)).quarter, at(date(2026, 4, 1)).quarter), (2, 3));
+        assert_eq!((at(date(2026, 6, 30)).quarter, at(date(2026, 7, 1)).quarter), (3, 4));
+        assert_eq!(at(date(2026, 1, 1)).week, 14);
+    }
+
+    #[test]
+    fn retail_years_start_on_the_nearest_weekday() {
+        // Feb 1 falls on a Wednesday, Thursday and Saturday in these years.
+        assert_eq!(retail_start(2023, 2, Weekday::Sun), date(2023, 1, 29));
+        assert_eq!(retail_start(2024, 2, Weekday::Sun), date(2024, 2, 4));
+        assert_eq!(retail_start(2025, 2, Weekday::Sun), date(2025, 2, 2));
+        assert_eq!(retail_start(2024, 1, Weekday::Mon), date(2024, 1, 1));
+    }
+
+    #[test]
+    fn retail_periods_follow_the_pattern() {
+        let periods = |pattern| [1, 4, 5, 8, 9, 13, 14, 52].map(|week| retail_period(week, pattern));
+        assert_eq!(periods(RetailPattern::FourFourFive), [1, 1, 2, 2, 3, 3, 4, 12]);
+        assert_eq!(periods(RetailPattern::FourFiveFour), [1, 1, 2, 2, 2, 3, 4, 12]);
+        assert_eq!(periods(RetailPattern::FiveFourFour), [1, 1, 1, 2, 2, 3, 4, 12]);
+    }
+
+    #[test]
+    fn retail_quarters_and_the_53rd_week() {
+        let retail = fiscal(2, Some(RetailPattern::FourFourFive));
+        let at = |d| retail.locate(d, Weekday::Sun);
+        assert_eq!(at(date(2024, 2, 4)), FiscalDate { year: 2025, quarter: 1, period: 1, week: 1 });
+        assert_eq!(at(date(2024, 5, 4)), FiscalDate { year: 2025, quarter: 1, period: 3, week: 13 });
+        assert_eq!(at(date(2024, 5, 5)), FiscalDate { year: 2025, quarter: 2, period: 4, week: 14 });
+        // The year from 2023-01-29 runs 53 weeks; the extra week joins period 12.
+        assert_eq!(at(date(2024, 1, 28)), FiscalDate { year: 2024, quarter: 4, period: 12, week: 53 });
+        assert_eq!(at(date(2024, 2, 3)), FiscalDate { year: 2024, quarter: 4, period: 12, week: 53 });
+        assert_eq!(retail_period(53, RetailPattern::FiveFourFour), 12);
+    }
+
+    #[test]
+    fn start_month_is_validated() {
+        assert!(fiscal(12, None).validate().is_ok());
+        assert!(fiscal(0, None).validate().is_err());
+        assert!(fiscal(13, None).validate().is_err());
+    }
+}
@@ -0,0 +1,341 @@
+//! Copy history: every value copied from the widget, the tray or a shortcut,
+//! kept in `history.json` so it survives restarts.
+//!
+//! The newest `historyLimit` entries are kept (default 500); pinned entries
+//! are never dropped. Copying the same value again, from the same preset and
+//! zone, moves it to the top instead of adding a duplicate. Each new entry is
+//! also emitted as `history-entry`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+use tauri_plugin_store::StoreExt;
+
+use crate::error::TimestampError;
+use crate::timestamp::{stored_pref, InstantInput};
+
+/// Event emitted with the new `HistoryEntry` after each copy.
+pub const HISTORY_EVENT: &str = "history-entry";
+const STORE: &str = "history.json";
+const DEFAULT_LIMIT: usize = 500;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CopySource {
+    #[default]
+    Widget,
+    Tray,
+    Shortcut,
+}
+
+impl CopySource {
+    fn name(self) -> &'static str {
+        match self {
+            CopySource::Widget => "widget",
+            CopySource::Tray => "tray",
+            CopySource::Shortcut => "shortcut",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub id: u64,
+    pub value: String,
+    /// Name of the preset that produced the value, if any.
+    pub preset: Option<String>,
+    pub timezone: Option<String>,
+    /// The instant the value was rendered for.
+    pub instant: DateTime<Utc>,
+    pub source: CopySource,
+    pub pinned: bool,
+}
+
+/// What the copy commands know about a value beyond its text.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CopyContext {
+    pub preset: Option<String>,
+    pub timezone: Option<String>,
+    /// Defaults to the time of the copy.
+    pub instant: Option<InstantInput>,
+    pub source: CopySource,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+/// Entries oldest first, as stored.
+#[derive(Default)]
+pub struct History(Mutex<Vec<HistoryEntry>>);
+
+impl History {
+    /// The history saved by a previous run; a missing or unreadable file starts empty.
+    pub fn load<R: Runtime>(app: &AppHandle<R>) -> History {
+        let entries = app
+            .store(STORE)
+            .ok()
+            .and_then(|store| store.get("entries"))
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default();
+        History(Mutex::new(entries))
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, Vec<HistoryEntry>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn persist<R: Runtime>(app: &AppHandle<R>, entries: &[HistoryEntry]) {
+    let Ok(store) = app.store(STORE) else { return };
+    match serde_json::to_value(entries) {
+        Ok(value) => store.set("entries", value),
+        Err(e) => eprintln!("Failed to save history: {}", e),
+    }
+}
+
+/// Add a copy to the history, drop the oldest unpinned entries over the
+/// limit, and notify the webview.
+pub(crate) fn record<R: Runtime>(app: &AppHandle<R>, value: &str, mut context: CopyContext) {
+    let Some(history) = app.try_state::<History>() else { return };
+    let limit = stored_pref::<usize>(app, "historyLimit").unwrap_or(DEFAULT_LIMIT);
+    let instant = context.instant.take().and_then(|i| i.resolve().ok()).unwrap_or_else(Utc::now);
+    let entry = {
+        let mut entries = history.entries();
+        let entry = add(&mut entries, value, context, instant, limit);
+        persist(app, &entries);
+        entry
+    };
+    let _ = app.emit(HISTORY_EVENT, &entry);
+}
+
+/// Append a new entry, replacing an earlier copy of the same value from the
+/// same preset and zone (keeping its pin), then trim to `limit`.
+fn add(
+    entries: &mut Vec<HistoryEntry>,
+    value: &str,
+    context: CopyContext,
+    instant: DateTime<Utc>,
+    limit: usize,
+) -> HistoryEntry {
+    let id = entries.iter().map(|e| e.id).max().map_or(1, |id| id + 1);
+    let mut pinned = false;
+    entries.retain(|e| {
+        let same = e.value == value && e.preset == context.preset && e.timezone == context.timezone;
+        pinned |= same && e.pinned;
+        !same
+    });
+    let entry = HistoryEntry {
+        id,
+        value: value.to_string(),
+        preset: context.preset,
+        timezone: context.timezone,
+        instant,
+        source: context.source,
+        pinned,
+    };
+    entries.push(entry.clone());
+    let mut excess = entries.len().saturating_sub(limit);
+    entries.retain(|e| {
+        let drop = excess > 0 && !e.pinned;
+        excess -= usize::from(drop);
+        !drop
+    });
+    entry
+}
+
+/// Pinned entries first, then newest first.
+fn sorted<'a>(entries: impl Iterator<Item = &'a HistoryEntry>, limit: Option<usize>) -> Vec<HistoryEntry> {
+    let mut list: Vec<HistoryEntry> = entries.cloned().collect();
+    list.sort_by(|a, b| b.pinned.cmp(&a.pinned).then(b.id.cmp(&a.id)));
+    list.truncate(limit.unwrap_or(usize::MAX));
+    list
+}
+
+#[tauri::command]
+pub fn list_history(history: State<'_, History>, limit: Option<usize>) -> Vec<HistoryEntry> {
+    sorted(history.entries().iter(), limit)
+}
+
+/// Whether `query` (already lowercased) occurs in the entry's value, preset,
+/// timezone or source.
+fn matches(e: &HistoryEntry, query: &str) -> bool {
+    [Some(e.value.as_str()), e.preset.as_deref(), e.timezone.as_deref(), Some(e.source.name())]
+        .into_iter()
+        .flatten()
+        .any(|field| field.to_lowercase().contains(query))
+}
+
+/// Entries whose value, preset, timezone or source contains `query`, ignoring case.
+#[tauri::command]
+pub fn search_history(history: State<'_, History>, query: String, limit: Option<usize>) -> Vec<HistoryEntry> {
+    let query = query.trim().to_lowercase();
+    let entries = history.entries();
+    sorted(entries.iter().filter(|e| matches(e, &query)), limit)
+}
+
+fn set_pinned(entries: &mut [HistoryEntry], id: u64, pinned: bool) -> Result<HistoryEntry, TimestampError> {
+    let entry = entries.iter_mut().find(|e| e.id == id).ok_or_else(|| TimestampError::InvalidSetting {
+        name: "history entry".into(),
+        message: format!("No history entry {}", id),
+    })?;
+    entry.pinned = pinned;
+    Ok(entry.clone())
+}
+
+#[tauri::command]
+pub fn pin_history_item(
+    app: AppHandle,
+    history: State<'_, History>,
+    id: u64,
+    pinned: bool,
+) -> Result<HistoryEntry, TimestampError> {
+    let mut entries = history.entries();
+    let entry = set_pinned(&mut entries, id, pinned)?;
+    persist(&app, &entries);
+    Ok(entry)
+}
+
+/// Remove unpinned entries, or everything with `include_pinned`.
+#[tauri::command]
+pub fn clear_history(app: AppHandle, history: State<'_, History>, include_pinned: Option<bool>) {
+    let mut entries = history.entries();
+    if include_pinned.unwrap_or(false) {
+        entries.clear();
+    } else {
+        entries.retain(|e| e.pinned);
+    }
+    persist(&app, &entries);
+}
+
+/// The whole history, oldest first, as CSV or a JSON array.
+#[tauri::command]
+pub fn export_history(history: State<'_, History>, format: ExportFormat) -> Result<String, TimestampError> {
+    export(&history.entries(), format)
+}
+
+fn export(entries: &[HistoryEntry], format: ExportFormat) -> Result<String, TimestampError> {
+    match format {
+        ExportFormat::Json => serde_json::to_string_pretty(entries)
+            .map_err(|e| TimestampError::InvalidSetting { name: "export".into(), message: e.to_string() }),
+        ExportFormat::Csv => {
+            let mut csv = String::from("id,instant,value,preset,timezone,source,pinned\n");
+            for e in entries.iter() {
+                let fields = [
+                    e.id.to_string(),
+                    e.instant.to_rfc3339(),
+                    e.value.clone(),
+                    e.preset.clone().unwrap_or_default(),
+                    e.timezone.clone().unwrap_or_default(),
+                    e.source.name().to_string(),
+                    e.pinned.to_string(),
+                ];
+                let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+                csv.push_str(&row.join(","));
+                csv.push('\n');
+            }
+            Ok(csv)
+        }
+    }
+}
+
+/// Quote fields containing separators, quotes or line breaks (RFC 4180).
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(preset: Option<&str>, source: CopySource) -> CopyContext {
+        CopyContext { preset: preset.map(str::to_string), timezone: Some("UTC".into()), instant: None, source }
+    }
+
+    fn copy(entries: &mut Vec<HistoryEntry>, value: &str, limit: usize) -> HistoryEntry {
+        add(entries, value, context(None, CopySource::Widget), Utc::now(), limit)
+    }
+
+    fn values(entries: &[HistoryEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.value.as_str()).collect()
+    }
+
+    #[test]
+    fn records_with_context_and_increasing_ids() {
+        let mut entries = Vec::new();
+        let first = add(&mut entries, "1700000000", context(Some("Unix"), CopySource::Tray), Utc::now(), 10);
+        let second = copy(&mut entries, "2023-11-14", 10);
+        assert_eq!((first.id, second.id), (1, 2));
+        assert_eq!((first.preset.as_deref(), first.source), (Some("Unix"), CopySource::Tray));
+        assert_eq!(values(&entries), ["1700000000", "2023-11-14"]);
+    }
+
+    #[test]
+    fn repeats_move_to_the_top_and_keep_their_pin() {
+        let mut entries = Vec::new();
+        copy(&mut entries, "a", 10);
+        copy(&mut entries, "b", 10);
+        set_pinned(&mut entries, 1, true).unwrap();
+        let again = copy(&mut entries, "a", 10);
+        assert_eq!(values(&entries), ["b", "a"]);
+        assert_eq!((again.id, again.pinned), (3, true));
+        // The same value from another preset is a separate entry.
+        add(&mut entries, "a", context(Some("Other"), CopySource::Widget), Utc::now(), 10);
+        assert_eq!(values(&entries), ["b", "a", "a"]);
+    }
+
+    #[test]
+    fn the_cap_drops_the_oldest_unpinned() {
+        let mut entries = Vec::new();
+        for value in ["a", "b", "c"] {
+            copy(&mut entries, value, 3);
+        }
+        set_pinned(&mut entries, 1, true).unwrap();
+        copy(&mut entries, "d", 3);
+        copy(&mut entries, "e", 3);
+        assert_eq!(values(&entries), ["a", "d", "e"]);
+    }
+
+    #[test]
+    fn search_and_pinned_first_order() {
+        let mut entries = Vec::new();
+        add(&mut entries, "1700000000", context(Some("Unix"), CopySource::Tray), Utc::now(), 10);
+        copy(&mut entries, "2023-11-14", 10);
+        copy(&mut entries, "Tuesday", 10);
+        let found: Vec<HistoryEntry> = entries.iter().filter(|e| matches(e, "unix")).cloned().collect();
+        assert_eq!(values(&found), ["1700000000"]);
+        assert_eq!(entries.iter().filter(|e| matches(e, "tray")).count(), 1);
+        assert_eq!(entries.iter().filter(|e| matches(e, "utc")).count(), 3);
+        set_pinned(&mut entries, 1, true).unwrap();
+        assert_eq!(values(&sorted(entries.iter(), None)), ["1700000000", "Tuesday", "2023-11-14"]);
+        assert_eq!(values(&sorted(entries.iter(), Some(2))), ["1700000000", "Tuesday"]);
+    }
+
+    #[test]
+    fn pinning_an_unknown_entry_is_an_error() {
+        let mut entries = Vec::new();
+        copy(&mut entries, "a", 10);
+        assert!(matches!(set_pinned(&mut entries, 9, true), Err(TimestampError::InvalidSetting { .. })));
+        assert!(!set_pinned(&mut entries, 1, false).unwrap().pinned);
+    }
+
+    #[test]
+    fn csv_export_quotes_fields() {
+        let mut entries = Vec::new();
+        copy(&mut entries, "Tue, 14 Nov", 10);
+        let csv = export(&entries, ExportFormat::Csv).unwrap();
+        let row = csv.lines().nth(1).unwrap();
+        assert!(row.starts_with("1,"));
+        assert!(row.contains(",\"Tue, 14 Nov\",,UTC,widget,false"));
+    }
+}
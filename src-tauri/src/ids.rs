@@ -0,0 +1,332 @@
+//! Time-ordered IDs: ULID, UUIDv7, KSUID and Snowflake.
+//!
+//! Each embeds its creation time, so the same instant that renders a
+//! timestamp can mint an ID, and `decode_id` can read the time back out.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::AppHandle;
+
+use crate::error::TimestampError;
+use crate::timestamp::{format_instant, stored_pref, InstantInput, RenderedTimestamp};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IdKind {
+    /// 48-bit milliseconds and 80 random bits, Crockford base32.
+    Ulid,
+    /// RFC 9562 version 7: 48-bit milliseconds and 74 random bits.
+    #[serde(alias = "uuid")]
+    Uuidv7,
+    /// Segment's K-Sortable ID: seconds since 2014-05-13 and 128 random bits, base62.
+    Ksuid,
+    /// Twitter/Discord style: milliseconds since a custom epoch, worker and sequence bits.
+    Snowflake,
+}
+
+/// Keywords accepted by `generate_timestamp`; the tokens use the same names.
+pub const IDS: &[(IdKind, &str)] = &[
+    (IdKind::Ulid, "ulid"),
+    (IdKind::Uuidv7, "uuidv7"),
+    (IdKind::Ksuid, "ksuid"),
+    (IdKind::Snowflake, "snowflake"),
+];
+
+/// Snowflake layout, from the `snowflake` preference. Defaults to Twitter's;
+/// Discord's epoch is 1420070400000.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SnowflakeConfig {
+    pub epoch_ms: i64,
+    pub worker_bits: u32,
+    pub sequence_bits: u32,
+    pub worker_id: u64,
+}
+
+impl Default for SnowflakeConfig {
+    fn default() -> Self {
+        SnowflakeConfig { epoch_ms: 1_288_834_974_657, worker_bits: 10, sequence_bits: 12, worker_id: 0 }
+    }
+}
+
+impl SnowflakeConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !matches!(self.worker_bits.checked_add(self.sequence_bits), Some(bits) if bits <= 32) {
+            return Err("Worker and sequence bits must leave at least 32 bits for the timestamp".into());
+        }
+        if self.worker_id >> self.worker_bits != 0 {
+            return Err(format!("Worker ID {} doesn't fit in {} bits", self.worker_id, self.worker_bits));
+        }
+        Ok(())
+    }
+
+    fn shift(&self) -> u32 {
+        self.worker_bits + self.sequence_bits
+    }
+}
+
+/// Seconds from the Unix epoch to the KSUID epoch.
+const KSUID_EPOCH: i64 = 1_400_000_000;
+const CROCKFORD: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const BASE62: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Snowflakes minted in the same millisecond differ by sequence.
+static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+impl IdKind {
+    pub fn from_keyword(keyword: &str) -> Option<IdKind> {
+        IDS.iter().find(|i| i.1 == keyword).map(|i| i.0)
+    }
+
+    pub fn keyword(self) -> &'static str {
+        IDS.iter().find(|i| i.0 == self).map_or("ulid", |i| i.1)
+    }
+
+    /// A new ID for `at`.
+    pub fn generate(self, at: DateTime<Utc>, snowflake: &SnowflakeConfig) -> String {
+        let millis = at.timestamp_millis().max(0) as u128 & 0xFFFF_FFFF_FFFF;
+        match self {
+            IdKind::Ulid => {
+                let id = millis << 80 | u128::from_be_bytes(random::<16>()) >> 48;
+                (0..26).rev().map(|i| CROCKFORD[(id >> (i * 5)) as usize & 31] as char).collect()
+            }
+            IdKind::Uuidv7 => {
+                let rand = u128::from_be_bytes(random::<16>());
+                let id = millis << 80 | 0x7 << 76 | (rand & 0xFFF) << 64 | 0b10 << 62 | rand >> 66;
+                let hex = format!("{:032x}", id);
+                format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+            }
+            IdKind::Ksuid => {
+                let seconds = (at.timestamp() - KSUID_EPOCH).clamp(0, u32::MAX.into()) as u32;
+                let mut bytes = [0u8; 20];
+                bytes[..4].copy_from_slice(&seconds.to_be_bytes());
+                bytes[4..].copy_from_slice(&random::<16>());
+                base62(&bytes)
+            }
+            IdKind::Snowflake => {
+                let elapsed = (at.timestamp_millis() - snowflake.epoch_ms).max(0) as u64;
+                let sequence = SEQUENCE.fetch_add(1, Ordering::Relaxed) & ((1 << snowflake.sequence_bits) - 1);
+                (elapsed << snowflake.shift() | snowflake.worker_id << snowflake.sequence_bits | sequence).to_string()
+            }
+        }
+    }
+
+    /// The instant embedded in `id`, if it is a well-formed ID of this kind.
+    pub fn decode(self, id: &str, snowflake: &SnowflakeConfig) -> Option<DateTime<Utc>> {
+        let id = id.trim();
+        match self {
+            IdKind::Ulid => {
+                if id.len() != 26 {
+                    return None;
+                }
+                let value = id.chars().try_fold(0u128, |acc, c| Some(acc.checked_mul(32)? | crockford(c)? as u128))?;
+                DateTime::from_timestamp_millis((value >> 80) as i64)
+            }
+            IdKind::Uuidv7 => {
+                let hex: String = id.chars().filter(|c| *c != '-').collect();
+                if hex.len() != 32 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) || hex.as_bytes()[12] != b'7' {
+                    return None;
+                }
+                DateTime::from_timestamp_millis(i64::from_str_radix(&hex[..12], 16).ok()?)
+            }
+            IdKind::Ksuid => {
+                let bytes = from_base62(id)?;
+                let seconds = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                DateTime::from_timestamp(i64::from(seconds) + KSUID_EPOCH, 0)
+            }
+            IdKind::Snowflake => {
+                let value: u64 = id.parse().ok()?;
+                // The layout may not have been validated, and a large ID or
+                // epoch can take the sum past what i64 milliseconds hold.
+                let shift = snowflake.worker_bits.checked_add(snowflake.sequence_bits)?;
+                let elapsed = i64::try_from(value.checked_shr(shift)?).ok()?;
+                DateTime::from_timestamp_millis(elapsed.checked_add(snowflake.epoch_ms)?)
+            }
+        }
+    }
+
+    /// Guess the kind of `id` from its shape.
+    fn detect(id: &str) -> Option<IdKind> {
+        let id = id.trim();
+        match id.len() {
+            26 => Some(IdKind::Ulid),
+            27 => Some(IdKind::Ksuid),
+            32 | 36 if id.contains('-') || id.bytes().all(|b| b.is_ascii_hexdigit()) => Some(IdKind::Uuidv7),
+            1..=20 if id.bytes().all(|b| b.is_ascii_digit()) => Some(IdKind::Snowflake),
+            _ => None,
+        }
+    }
+}
+
+fn random<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    // Without an OS source the ID is still well-formed, just not unique.
+    let _ = getrandom::fill(&mut bytes);
+    bytes
+}
+
+/// Crockford base32 digit, accepting lowercase and the I/L/O look-alikes.
+fn crockford(c: char) -> Option<u8> {
+    let c = match c.to_ascii_uppercase() {
+        'I' | 'L' => '1',
+        'O' => '0',
+        c => c,
+    };
+    CROCKFORD.iter().position(|&d| d as char == c).map(|i| i as u8)
+}
+
+/// 20 bytes as 27 base62 digits, most significant first.
+fn base62(bytes: &[u8; 20]) -> String {
+    let mut number = bytes.to_vec();
+    let mut digits = Vec::with_capacity(27);
+    for _ in 0..27 {
+        let mut remainder = 0u32;
+        for byte in number.iter_mut() {
+            let value = remainder << 8 | u32::from(*byte);
+            *byte = (value / 62) as u8;
+            remainder = value % 62;
+        }
+        digits.push(BASE62[remainder as usize]);
+    }
+    digits.iter().rev().map(|&d| d as char).collect()
+}
+
+fn from_base62(s: &str) -> Option<[u8; 20]> {
+    if s.len() != 27 {
+        return None;
+    }
+    let mut bytes = [0u8; 20];
+    for c in s.bytes() {
+        let mut carry = BASE62.iter().position(|&d| d == c)? as u32;
+        for byte in bytes.iter_mut().rev() {
+            let value = u32::from(*byte) * 62 + carry;
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+        if carry != 0 {
+            return None;
+        }
+    }
+    Some(bytes)
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedId {
+    pub kind: IdKind,
+    /// Pass straight back to `format_instant` to re-render in any preset.
+    pub instant: InstantInput,
+    pub rendered: RenderedTimestamp,
+}
+
+/// Extract the timestamp from a ULID, UUIDv7, KSUID or Snowflake and render
+/// it with `format` (default `iso`). `kind` is guessed from the ID's shape
+/// when omitted; `snowflake` overrides the stored layout.
+#[tauri::command]
+pub fn decode_id(
+    app: AppHandle,
+    id: String,
+    kind: Option<IdKind>,
+    format: Option<String>,
+    timezone: Option<String>,
+    snowflake: Option<SnowflakeConfig>,
+) -> Result<DecodedId, TimestampError> {
+    let invalid = |message: String| TimestampError::InvalidInstant { message };
+    let layout = match snowflake {
+        Some(s) => {
+            s.validate().map_err(|message| TimestampError::InvalidSetting { name: "snowflake".into(), message })?;
+            s
+        }
+        None => snowflake_pref(&app),
+    };
+    let kind = kind.or_else(|| IdKind::detect(&id)).ok_or_else(|| invalid(format!("Unrecognized ID: '{}'", id.trim())))?;
+    let at = kind
+        .decode(&id, &layout)
+        .ok_or_else(|| invalid(format!("Not a valid {} ID: '{}'", kind.keyword(), id.trim())))?;
+    let instant = InstantInput::Utc(at);
+    let rendered = format_instant(app, instant.clone(), format.unwrap_or_else(|| "iso".into()), timezone, None, None, None, None)?;
+    Ok(DecodedId { kind, instant, rendered })
+}
+
+/// The stored Snowflake layout, ignoring an invalid one.
+pub(crate) fn snowflake_pref(app: &AppHandle) -> SnowflakeConfig {
+    stored_pref::<SnowflakeConfig>(app, "snowflake").filter(|s| s.validate().is_ok()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(epoch_ms: i64, worker_bits: u32, sequence_bits: u32) -> SnowflakeConfig {
+        SnowflakeConfig { epoch_ms, worker_bits, sequence_bits, worker_id: 0 }
+    }
+
+    #[test]
+    fn snowflakes_decode_with_their_epoch() {
+        let discord = layout(1_420_070_400_000, 10, 12);
+        let at = IdKind::Snowflake.decode("175928847299117063", &discord).unwrap();
+        assert_eq!(at.to_rfc3339(), "2016-04-30T11:18:25.796+00:00");
+    }
+
+    #[test]
+    fn snowflake_overflow_is_rejected() {
+        let max = u64::MAX.to_string();
+        assert_eq!(IdKind::Snowflake.decode(&max, &layout(0, 0, 0)), None);
+        assert_eq!(IdKind::Snowflake.decode(&max, &layout(i64::MAX, 10, 12)), None);
+        assert_eq!(IdKind::Snowflake.decode("1", &layout(0, u32::MAX, 1)), None);
+        assert!(layout(0, u32::MAX, 1).validate().is_err());
+        assert!(layout(0, 10, 12).validate().is_ok());
+        assert!(layout(0, 16, 16).validate().is_ok());
+        assert!(layout(0, 16, 17).validate().is_err());
+    }
+
+    #[test]
+    fn published_examples_decode() {
+        let decode = |kind: IdKind, id| kind.decode(id, &SnowflakeConfig::default()).map(|at| at.to_rfc3339());
+        assert_eq!(decode(IdKind::Ulid, "01ARZ3NDEKTSV4RRFFQ69G5FAV").as_deref(), Some("2016-07-30T23:54:10.259+00:00"));
+        assert_eq!(
+            decode(IdKind::Uuidv7, "017F22E2-79B0-7CC3-98C4-DC0C0C07398F").as_deref(),
+            Some("2022-02-22T19:22:22+00:00")
+        );
+        assert_eq!(decode(IdKind::Ksuid, "0ujtsYcgvSTl8PAuAdqWYSMnLOv").as_deref(), Some("2017-10-10T04:00:47+00:00"));
+    }
+
+    #[test]
+    fn generated_ids_round_trip() {
+        let at = DateTime::from_timestamp_millis(1_700_000_000_123).unwrap();
+        let config = SnowflakeConfig::default();
+        for &(kind, keyword) in IDS {
+            let id = kind.generate(at, &config);
+            assert_eq!(IdKind::detect(&id), Some(kind), "{} {}", keyword, id);
+            let expected = match kind {
+                IdKind::Ksuid => DateTime::from_timestamp(1_700_000_000, 0),
+                _ => Some(at),
+            };
+            assert_eq!(kind.decode(&id, &config), expected, "{} {}", keyword, id);
+        }
+        // Crockford look-alikes and lowercase read as their digits.
+        let ulid = IdKind::Ulid.generate(at, &config).to_lowercase().replace('0', "o").replace('1', "l");
+        assert_eq!(IdKind::Ulid.decode(&ulid, &config), Some(at));
+    }
+
+    #[test]
+    fn malformed_ids_are_rejected() {
+        let config = SnowflakeConfig::default();
+        let invalid = [
+            (IdKind::Ulid, "01ARZ3NDEKTSV4RRFFQ69G5FA"),
+            (IdKind::Ulid, "01ARZ3NDEKTSV4RRFFQ69G5FAU"),
+            (IdKind::Ulid, "81ARZ3NDEKTSV4RRFFQ69G5FAV"),
+            (IdKind::Uuidv7, "017f22e2-79b0-4cc3-98c4-dc0c0c07398f"),
+            (IdKind::Uuidv7, "017f22e2-79b0-7cc3-98c4-dc0c0c07398"),
+            (IdKind::Uuidv7, "017f22e2-79b0-7cc3-98c4-dc0c0c07398g"),
+            (IdKind::Ksuid, "0ujtsYcgvSTl8PAuAdqWYSMnLO"),
+            (IdKind::Ksuid, "0ujtsYcgvSTl8PAuAdqWYSMnLO-"),
+            (IdKind::Ksuid, "zzzzzzzzzzzzzzzzzzzzzzzzzzz"),
+        ];
+        for (kind, id) in invalid {
+            assert_eq!(kind.decode(id, &config), None, "{}", id);
+        }
+        assert_eq!(IdKind::detect("not an id"), None);
+    }
+}
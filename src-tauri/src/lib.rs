@@ -1,7 +1,25 @@
+mod error;
 mod tray;
 mod timestamp;
 mod shortcuts;
 mod prefs;
+mod template;
+mod locale;
+mod zones;
+mod weeks;
+mod seasons;
+mod fiscal;
+mod calendars;
+mod epochs;
+mod ids;
+mod markup;
+mod clipboard;
+mod history;
+mod presets;
+mod relative;
+mod parse;
+mod convert;
+mod code;
 use tauri::Manager;
 use tauri_plugin_store::StoreExt;
 
@@ -22,11 +40,31 @@ pub fn run() {
                 .build(),
         )
         .manage(prefs_state.clone())
+        .manage(timestamp::FormatCache::default())
         .invoke_handler(tauri::generate_handler![
             timestamp::generate_timestamp,
             timestamp::get_current_formats,
             timestamp::format_tokens,
+            timestamp::format_instant,
+            timestamp::validate_format,
+            timestamp::list_tokens,
+            parse::parse_timestamp,
+            ids::decode_id,
+            convert::convert_format,
+            code::format_to_code,
             timestamp::copy_to_clipboard,
+            clipboard::copy_rich,
+            clipboard::copy_preset,
+            history::list_history,
+            history::search_history,
+            history::pin_history_item,
+            history::clear_history,
+            history::export_history,
+            presets::list_presets,
+            presets::create_preset,
+            presets::update_preset,
+            presets::delete_preset,
+            presets::reorder_presets,
             shortcuts::register_global_shortcut,
             shortcuts::unregister_shortcut,
             prefs::set_close_on_blur,
@@ -48,6 +86,9 @@ pub fn run() {
                 if let Some(v) = store.get("alwaysOnTop") { if let Some(b) = v.as_bool() { prefs_state.always_on_top.store(b, std::sync::atomic::Ordering::Relaxed); } }
             }
 
+            app.manage(history::History::load(app.handle()));
+            presets::migrate(app.handle());
+
             // Create system tray
             tray::create_tray(app.handle())?;
             
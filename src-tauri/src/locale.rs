@@ -0,0 +1,625 @@
+//! Bundled, offline locale data for the token engine.
+//!
+//! Languages that inflect month names inside a date ("14 ноября" but
+//! "ноябрь 2024") carry both forms; `{month}` uses the in-date one when it
+//! directly follows `{date}` or `{day-ordinal}`.
+
+use crate::relative::RelativeUnit;
+
+pub struct Locale {
+    pub tag: &'static str,
+    /// Sunday first, matching `num_days_from_sunday`.
+    pub days: [&'static str; 7],
+    pub days_abb: [&'static str; 7],
+    /// Standalone (nominative) month names, as in "ноябрь 2024".
+    pub months: [&'static str; 12],
+    /// Month names after the day number, where they differ from `months`:
+    /// the genitive in Russian and Polish.
+    pub months_in_date: Option<[&'static str; 12]>,
+    pub months_abb: [&'static str; 12],
+    /// Spring, summer, autumn, winter.
+    pub seasons: [&'static str; 4],
+    /// `{season-day}`: `{n}` is the day within the season, `{season}` its name.
+    pub season_day: &'static str,
+    pub am: &'static str,
+    pub pm: &'static str,
+    /// Whether the locale's everyday clock is 12-hour.
+    pub hour12: bool,
+    /// Layout of the 12-hour clock: `h` hour, `mm` minutes, `a` period marker.
+    pub time12: &'static str,
+    ordinal: fn(u32) -> String,
+    pub relative: &'static RelativeWords,
+}
+
+/// Wording for `{relative}`.
+pub struct RelativeWords {
+    pub now: &'static str,
+    pub today: &'static str,
+    pub yesterday: &'static str,
+    pub tomorrow: &'static str,
+    /// `{day}` is yesterday/tomorrow, `{time}` the locale clock.
+    pub day_at: &'static str,
+    /// `{}` is the quantity, e.g. "3 days".
+    pub past: &'static str,
+    pub future: &'static str,
+    /// Quantity with its unit, inflected for the count and for use inside
+    /// `past`/`future`.
+    pub unit: fn(RelativeUnit, i64) -> String,
+}
+
+impl Locale {
+    pub fn ordinal(&self, n: u32) -> String {
+        (self.ordinal)(n)
+    }
+
+    /// The name of month `month0` (0 = January), in its in-date form if
+    /// `in_date` and the locale has one.
+    pub fn month(&self, month0: usize, in_date: bool) -> &'static str {
+        match self.months_in_date {
+            Some(names) if in_date => names[month0],
+            _ => self.months[month0],
+        }
+    }
+
+    pub fn period(&self, is_pm: bool) -> &'static str {
+        if is_pm { self.pm } else { self.am }
+    }
+
+    /// The locale's everyday clock, e.g. "5:13 PM", "17:13" or "午後5:13".
+    pub fn clock(&self, hour24: u32, minute: u32) -> String {
+        if !self.hour12 {
+            return format!("{:02}:{:02}", hour24, minute);
+        }
+        let hour = match hour24 % 12 { 0 => 12, h => h };
+        let mut out = String::new();
+        let mut chars = self.time12.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                'h' => out.push_str(&hour.to_string()),
+                'm' => {
+                    chars.next_if_eq(&'m');
+                    out.push_str(&format!("{:02}", minute));
+                }
+                'a' => out.push_str(self.period(hour24 >= 12)),
+                other => out.push(other),
+            }
+        }
+        out
+    }
+
+    /// Resolve a BCP 47 tag, falling back to another region of the same
+    /// language and finally to `en-US`.
+    pub fn resolve(tag: &str) -> &'static Locale {
+        Locale::lookup(tag).unwrap_or(&LOCALES[0])
+    }
+
+    /// The locale for a BCP 47 tag, or another region of the same language;
+    /// `None` if the language isn't in the table.
+    pub fn lookup(tag: &str) -> Option<&'static Locale> {
+        let tag = tag.trim().replace('_', "-");
+        if let Some(l) = LOCALES.iter().find(|l| l.tag.eq_ignore_ascii_case(&tag)) {
+            return Some(l);
+        }
+        let lang = tag.split('-').next().unwrap_or("");
+        LOCALES.iter().find(|l| l.tag.split('-').next().is_some_and(|p| p.eq_ignore_ascii_case(lang)))
+    }
+}
+
+fn ordinal_en(n: u32) -> String {
+    let suffix = if (11..=13).contains(&(n % 100)) {
+        "th"
+    } else {
+        match n % 10 {
+            1 => "st",
+            2 => "nd",
+            3 => "rd",
+            _ => "th",
+        }
+    };
+    format!("{}{}", n, suffix)
+}
+
+fn ordinal_fr(n: u32) -> String {
+    if n == 1 { "1er".into() } else { format!("{}e", n) }
+}
+
+fn ordinal_dot(n: u32) -> String {
+    format!("{}.", n)
+}
+
+fn ordinal_masculine(n: u32) -> String {
+    format!("{}º", n)
+}
+
+fn ordinal_es(n: u32) -> String {
+    format!("{}.º", n)
+}
+
+fn ordinal_nl(n: u32) -> String {
+    format!("{}e", n)
+}
+
+fn ordinal_ru(n: u32) -> String {
+    format!("{}-е", n)
+}
+
+fn ordinal_cjk_day(n: u32) -> String {
+    format!("{}日", n)
+}
+
+fn ordinal_ko(n: u32) -> String {
+    format!("{}일", n)
+}
+
+fn ordinal_plain(n: u32) -> String {
+    n.to_string()
+}
+
+fn plural_en(unit: RelativeUnit, n: i64) -> String {
+    let word = match unit {
+        RelativeUnit::Second => "second",
+        RelativeUnit::Minute => "minute",
+        RelativeUnit::Hour => "hour",
+        RelativeUnit::Day => "day",
+        RelativeUnit::Week => "week",
+        RelativeUnit::Month => "month",
+        RelativeUnit::Year => "year",
+    };
+    format!("{} {}{}", n, word, if n == 1 { "" } else { "s" })
+}
+
+fn plural_fr(unit: RelativeUnit, n: i64) -> String {
+    let (one, other) = match unit {
+        RelativeUnit::Second => ("seconde", "secondes"),
+        RelativeUnit::Minute => ("minute", "minutes"),
+        RelativeUnit::Hour => ("heure", "heures"),
+        RelativeUnit::Day => ("jour", "jours"),
+        RelativeUnit::Week => ("semaine", "semaines"),
+        RelativeUnit::Month => ("mois", "mois"),
+        RelativeUnit::Year => ("an", "ans"),
+    };
+    format!("{} {}", n, if n <= 1 { one } else { other })
+}
+
+fn plural_es(unit: RelativeUnit, n: i64) -> String {
+    let (one, other) = match unit {
+        RelativeUnit::Second => ("segundo", "segundos"),
+        RelativeUnit::Minute => ("minuto", "minutos"),
+        RelativeUnit::Hour => ("hora", "horas"),
+        RelativeUnit::Day => ("día", "días"),
+        RelativeUnit::Week => ("semana", "semanas"),
+        RelativeUnit::Month => ("mes", "meses"),
+        RelativeUnit::Year => ("año", "años"),
+    };
+    format!("{} {}", n, if n == 1 { one } else { other })
+}
+
+/// Dative forms, which both "vor" and "in" take.
+fn plural_de(unit: RelativeUnit, n: i64) -> String {
+    let (one, other) = match unit {
+        RelativeUnit::Second => ("Sekunde", "Sekunden"),
+        RelativeUnit::Minute => ("Minute", "Minuten"),
+        RelativeUnit::Hour => ("Stunde", "Stunden"),
+        RelativeUnit::Day => ("Tag", "Tagen"),
+        RelativeUnit::Week => ("Woche", "Wochen"),
+        RelativeUnit::Month => ("Monat", "Monaten"),
+        RelativeUnit::Year => ("Jahr", "Jahren"),
+    };
+    format!("{} {}", n, if n == 1 { one } else { other })
+}
+
+fn plural_it(unit: RelativeUnit, n: i64) -> String {
+    let (one, other) = match unit {
+        RelativeUnit::Second => ("secondo", "secondi"),
+        RelativeUnit::Minute => ("minuto", "minuti"),
+        RelativeUnit::Hour => ("ora", "ore"),
+        RelativeUnit::Day => ("giorno", "giorni"),
+        RelativeUnit::Week => ("settimana", "settimane"),
+        RelativeUnit::Month => ("mese", "mesi"),
+        RelativeUnit::Year => ("anno", "anni"),
+    };
+    format!("{} {}", n, if n == 1 { one } else { other })
+}
+
+fn plural_pt(unit: RelativeUnit, n: i64) -> String {
+    let (one, other) = match unit {
+        RelativeUnit::Second => ("segundo", "segundos"),
+        RelativeUnit::Minute => ("minuto", "minutos"),
+        RelativeUnit::Hour => ("hora", "horas"),
+        RelativeUnit::Day => ("dia", "dias"),
+        RelativeUnit::Week => ("semana", "semanas"),
+        RelativeUnit::Month => ("mês", "meses"),
+        RelativeUnit::Year => ("ano", "anos"),
+    };
+    format!("{} {}", n, if n == 1 { one } else { other })
+}
+
+fn plural_nl(unit: RelativeUnit, n: i64) -> String {
+    let (one, other) = match unit {
+        RelativeUnit::Second => ("seconde", "seconden"),
+        RelativeUnit::Minute => ("minuut", "minuten"),
+        RelativeUnit::Hour => ("uur", "uur"),
+        RelativeUnit::Day => ("dag", "dagen"),
+        RelativeUnit::Week => ("week", "weken"),
+        RelativeUnit::Month => ("maand", "maanden"),
+        RelativeUnit::Year => ("jaar", "jaar"),
+    };
+    format!("{} {}", n, if n == 1 { one } else { other })
+}
+
+/// Slavic one/few/many selection shared by Russian and Polish.
+fn slavic_form<'a>(n: i64, one: &'a str, few: &'a str, many: &'a str, polish: bool) -> &'a str {
+    let (m10, m100) = (n % 10, n % 100);
+    if (polish && n == 1) || (!polish && m10 == 1 && m100 != 11) {
+        one
+    } else if (2..=4).contains(&m10) && !(12..=14).contains(&m100) {
+        few
+    } else {
+        many
+    }
+}
+
+/// Accusative forms, which both "назад" and "через" take.
+fn plural_ru(unit: RelativeUnit, n: i64) -> String {
+    let (one, few, many) = match unit {
+        RelativeUnit::Second => ("секунду", "секунды", "секунд"),
+        RelativeUnit::Minute => ("минуту", "минуты", "минут"),
+        RelativeUnit::Hour => ("час", "часа", "часов"),
+        RelativeUnit::Day => ("день", "дня", "дней"),
+        RelativeUnit::Week => ("неделю", "недели", "недель"),
+        RelativeUnit::Month => ("месяц", "месяца", "месяцев"),
+        RelativeUnit::Year => ("год", "года", "лет"),
+    };
+    format!("{} {}", n, slavic_form(n, one, few, many, false))
+}
+
+fn plural_pl(unit: RelativeUnit, n: i64) -> String {
+    let (one, few, many) = match unit {
+        RelativeUnit::Second => ("sekundę", "sekundy", "sekund"),
+        RelativeUnit::Minute => ("minutę", "minuty", "minut"),
+        RelativeUnit::Hour => ("godzinę", "godziny", "godzin"),
+        RelativeUnit::Day => ("dzień", "dni", "dni"),
+        RelativeUnit::Week => ("tydzień", "tygodnie", "tygodni"),
+        RelativeUnit::Month => ("miesiąc", "miesiące", "miesięcy"),
+        RelativeUnit::Year => ("rok", "lata", "lat"),
+    };
+    format!("{} {}", n, slavic_form(n, one, few, many, true))
+}
+
+fn plural_ja(unit: RelativeUnit, n: i64) -> String {
+    let word = match unit {
+        RelativeUnit::Second => "秒",
+        RelativeUnit::Minute => "分",
+        RelativeUnit::Hour => "時間",
+        RelativeUnit::Day => "日",
+        RelativeUnit::Week => "週間",
+        RelativeUnit::Month => "か月",
+        RelativeUnit::Year => "年",
+    };
+    format!("{}{}", n, word)
+}
+
+fn plural_zh(unit: RelativeUnit, n: i64) -> String {
+    let word = match unit {
+        RelativeUnit::Second => "秒",
+        RelativeUnit::Minute => "分钟",
+        RelativeUnit::Hour => "小时",
+        RelativeUnit::Day => "天",
+        RelativeUnit::Week => "周",
+        RelativeUnit::Month => "个月",
+        RelativeUnit::Year => "年",
+    };
+    format!("{}{}", n, word)
+}
+
+fn plural_ko(unit: RelativeUnit, n: i64) -> String {
+    let word = match unit {
+        RelativeUnit::Second => "초",
+        RelativeUnit::Minute => "분",
+        RelativeUnit::Hour => "시간",
+        RelativeUnit::Day => "일",
+        RelativeUnit::Week => "주",
+        RelativeUnit::Month => "개월",
+        RelativeUnit::Year => "년",
+    };
+    format!("{}{}", n, word)
+}
+
+/// Singular, dual, plural (3–10) and accusative singular (11+) forms.
+fn plural_ar(unit: RelativeUnit, n: i64) -> String {
+    let (one, two, few, many) = match unit {
+        RelativeUnit::Second => ("ثانية", "ثانيتين", "ثوان", "ثانية"),
+        RelativeUnit::Minute => ("دقيقة", "دقيقتين", "دقائق", "دقيقة"),
+        RelativeUnit::Hour => ("ساعة", "ساعتين", "ساعات", "ساعة"),
+        RelativeUnit::Day => ("يوم", "يومين", "أيام", "يومًا"),
+        RelativeUnit::Week => ("أسبوع", "أسبوعين", "أسابيع", "أسبوعًا"),
+        RelativeUnit::Month => ("شهر", "شهرين", "أشهر", "شهرًا"),
+        RelativeUnit::Year => ("سنة", "سنتين", "سنوات", "سنة"),
+    };
+    match n {
+        1 => one.to_string(),
+        2 => two.to_string(),
+        _ if (3..=10).contains(&(n % 100)) => format!("{} {}", n, few),
+        _ => format!("{} {}", n, many),
+    }
+}
+
+static RELATIVE_EN: RelativeWords = RelativeWords {
+    now: "just now", today: "today", yesterday: "yesterday", tomorrow: "tomorrow",
+    day_at: "{day} at {time}", past: "{} ago", future: "in {}", unit: plural_en,
+};
+static RELATIVE_FR: RelativeWords = RelativeWords {
+    now: "à l’instant", today: "aujourd’hui", yesterday: "hier", tomorrow: "demain",
+    day_at: "{day} à {time}", past: "il y a {}", future: "dans {}", unit: plural_fr,
+};
+static RELATIVE_ES: RelativeWords = RelativeWords {
+    now: "ahora mismo", today: "hoy", yesterday: "ayer", tomorrow: "mañana",
+    day_at: "{day} a las {time}", past: "hace {}", future: "dentro de {}", unit: plural_es,
+};
+static RELATIVE_DE: RelativeWords = RelativeWords {
+    now: "gerade eben", today: "heute", yesterday: "gestern", tomorrow: "morgen",
+    day_at: "{day} um {time}", past: "vor {}", future: "in {}", unit: plural_de,
+};
+static RELATIVE_IT: RelativeWords = RelativeWords {
+    now: "proprio ora", today: "oggi", yesterday: "ieri", tomorrow: "domani",
+    day_at: "{day} alle {time}", past: "{} fa", future: "tra {}", unit: plural_it,
+};
+static RELATIVE_PT: RelativeWords = RelativeWords {
+    now: "agora mesmo", today: "hoje", yesterday: "ontem", tomorrow: "amanhã",
+    day_at: "{day} às {time}", past: "há {}", future: "em {}", unit: plural_pt,
+};
+static RELATIVE_NL: RelativeWords = RelativeWords {
+    now: "zojuist", today: "vandaag", yesterday: "gisteren", tomorrow: "morgen",
+    day_at: "{day} om {time}", past: "{} geleden", future: "over {}", unit: plural_nl,
+};
+static RELATIVE_RU: RelativeWords = RelativeWords {
+    now: "только что", today: "сегодня", yesterday: "вчера", tomorrow: "завтра",
+    day_at: "{day} в {time}", past: "{} назад", future: "через {}", unit: plural_ru,
+};
+static RELATIVE_PL: RelativeWords = RelativeWords {
+    now: "przed chwilą", today: "dzisiaj", yesterday: "wczoraj", tomorrow: "jutro",
+    day_at: "{day} o {time}", past: "{} temu", future: "za {}", unit: plural_pl,
+};
+static RELATIVE_JA: RelativeWords = RelativeWords {
+    now: "たった今", today: "今日", yesterday: "昨日", tomorrow: "明日",
+    day_at: "{day} {time}", past: "{}前", future: "{}後", unit: plural_ja,
+};
+static RELATIVE_ZH: RelativeWords = RelativeWords {
+    now: "刚刚", today: "今天", yesterday: "昨天", tomorrow: "明天",
+    day_at: "{day} {time}", past: "{}前", future: "{}后", unit: plural_zh,
+};
+static RELATIVE_KO: RelativeWords = RelativeWords {
+    now: "방금", today: "오늘", yesterday: "어제", tomorrow: "내일",
+    day_at: "{day} {time}", past: "{} 전", future: "{} 후", unit: plural_ko,
+};
+static RELATIVE_AR: RelativeWords = RelativeWords {
+    now: "الآن", today: "اليوم", yesterday: "أمس", tomorrow: "غدًا",
+    day_at: "{day} في {time}", past: "منذ {}", future: "خلال {}", unit: plural_ar,
+};
+
+pub static LOCALES: &[Locale] = &[
+    Locale {
+        tag: "en-US",
+        days: ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"],
+        days_abb: ["Sun.", "Mon.", "Tue.", "Wed.", "Thu.", "Fri.", "Sat."],
+        months: ["January", "February", "March", "April", "May", "June", "July", "August", "September", "October", "November", "December"],
+        months_in_date: None,
+        months_abb: ["Jan.", "Feb.", "Mar.", "Apr.", "May", "Jun.", "Jul.", "Aug.", "Sep.", "Oct.", "Nov.", "Dec."],
+        seasons: ["Spring", "Summer", "Autumn", "Winter"],
+        season_day: "day {n} of {season}",
+        am: "AM",
+        pm: "PM",
+        hour12: true,
+        time12: "h:mm a",
+        ordinal: ordinal_en,
+        relative: &RELATIVE_EN,
+    },
+    Locale {
+        tag: "en-GB",
+        days: ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"],
+        days_abb: ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"],
+        months: ["January", "February", "March", "April", "May", "June", "July", "August", "September", "October", "November", "December"],
+        months_in_date: None,
+        months_abb: ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sept", "Oct", "Nov", "Dec"],
+        seasons: ["Spring", "Summer", "Autumn", "Winter"],
+        season_day: "day {n} of {season}",
+        am: "am",
+        pm: "pm",
+        hour12: false,
+        time12: "h:mm a",
+        ordinal: ordinal_en,
+        relative: &RELATIVE_EN,
+    },
+    Locale {
+        tag: "fr-FR",
+        days: ["dimanche", "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi"],
+        days_abb: ["dim.", "lun.", "mar.", "mer.", "jeu.", "ven.", "sam."],
+        months: ["janvier", "février", "mars", "avril", "mai", "juin", "juillet", "août", "septembre", "octobre", "novembre", "décembre"],
+        months_in_date: None,
+        months_abb: ["janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.", "nov.", "déc."],
+        seasons: ["printemps", "été", "automne", "hiver"],
+        season_day: "{season}, jour {n}",
+        am: "AM",
+        pm: "PM",
+        hour12: false,
+        time12: "h:mm a",
+        ordinal: ordinal_fr,
+        relative: &RELATIVE_FR,
+    },
+    Locale {
+        tag: "es-ES",
+        days: ["domingo", "lunes", "martes", "miércoles", "jueves", "viernes", "sábado"],
+        days_abb: ["dom.", "lun.", "mar.", "mié.", "jue.", "vie.", "sáb."],
+        months: ["enero", "febrero", "marzo", "abril", "mayo", "junio", "julio", "agosto", "septiembre", "octubre", "noviembre", "diciembre"],
+        months_in_date: None,
+        months_abb: ["ene.", "feb.", "mar.", "abr.", "may.", "jun.", "jul.", "ago.", "sept.", "oct.", "nov.", "dic."],
+        seasons: ["primavera", "verano", "otoño", "invierno"],
+        season_day: "{season}, día {n}",
+        am: "a. m.",
+        pm: "p. m.",
+        hour12: false,
+        time12: "h:mm a",
+        ordinal: ordinal_es,
+        relative: &RELATIVE_ES,
+    },
+    Locale {
+        tag: "de-DE",
+        days: ["Sonntag", "Montag", "Dienstag", "Mittwoch", "Donnerstag", "Freitag", "Samstag"],
+        days_abb: ["So.", "Mo.", "Di.", "Mi.", "Do.", "Fr.", "Sa."],
+        months: ["Januar", "Februar", "März", "April", "Mai", "Juni", "Juli", "August", "September", "Oktober", "November", "Dezember"],
+        months_in_date: None,
+        months_abb: ["Jan.", "Feb.", "März", "Apr.", "Mai", "Juni", "Juli", "Aug.", "Sept.", "Okt.", "Nov.", "Dez."],
+        seasons: ["Frühling", "Sommer", "Herbst", "Winter"],
+        season_day: "{season}, Tag {n}",
+        am: "AM",
+        pm: "PM",
+        hour12: false,
+        time12: "h:mm a",
+        ordinal: ordinal_dot,
+        relative: &RELATIVE_DE,
+    },
+    Locale {
+        tag: "it-IT",
+        days: ["domenica", "lunedì", "martedì", "mercoledì", "giovedì", "venerdì", "sabato"],
+        days_abb: ["dom", "lun", "mar", "mer", "gio", "ven", "sab"],
+        months: ["gennaio", "febbraio", "marzo", "aprile", "maggio", "giugno", "luglio", "agosto", "settembre", "ottobre", "novembre", "dicembre"],
+        months_in_date: None,
+        months_abb: ["gen", "feb", "mar", "apr", "mag", "giu", "lug", "ago", "set", "ott", "nov", "dic"],
+        seasons: ["primavera", "estate", "autunno", "inverno"],
+        season_day: "{season}, giorno {n}",
+        am: "AM",
+        pm: "PM",
+        hour12: false,
+        time12: "h:mm a",
+        ordinal: ordinal_masculine,
+        relative: &RELATIVE_IT,
+    },
+    Locale {
+        tag: "pt-BR",
+        days: ["domingo", "segunda-feira", "terça-feira", "quarta-feira", "quinta-feira", "sexta-feira", "sábado"],
+        days_abb: ["dom.", "seg.", "ter.", "qua.", "qui.", "sex.", "sáb."],
+        months: ["janeiro", "fevereiro", "março", "abril", "maio", "junho", "julho", "agosto", "setembro", "outubro", "novembro", "dezembro"],
+        months_in_date: None,
+        months_abb: ["jan.", "fev.", "mar.", "abr.", "mai.", "jun.", "jul.", "ago.", "set.", "out.", "nov.", "dez."],
+        seasons: ["primavera", "verão", "outono", "inverno"],
+        season_day: "{season}, dia {n}",
+        am: "AM",
+        pm: "PM",
+        hour12: false,
+        time12: "h:mm a",
+        ordinal: ordinal_masculine,
+        relative: &RELATIVE_PT,
+    },
+    Locale {
+        tag: "nl-NL",
+        days: ["zondag", "maandag", "dinsdag", "woensdag", "donderdag", "vrijdag", "zaterdag"],
+        days_abb: ["zo", "ma", "di", "wo", "do", "vr", "za"],
+        months: ["januari", "februari", "maart", "april", "mei", "juni", "juli", "augustus", "september", "oktober", "november", "december"],
+        months_in_date: None,
+        months_abb: ["jan", "feb", "mrt", "apr", "mei", "jun", "jul", "aug", "sep", "okt", "nov", "dec"],
+        seasons: ["lente", "zomer", "herfst", "winter"],
+        season_day: "{season}, dag {n}",
+        am: "a.m.",
+        pm: "p.m.",
+        hour12: false,
+        time12: "h:mm a",
+        ordinal: ordinal_nl,
+        relative: &RELATIVE_NL,
+    },
+    Locale {
+        tag: "ru-RU",
+        days: ["воскресенье", "понедельник", "вторник", "среда", "четверг", "пятница", "суббота"],
+        days_abb: ["вс", "пн", "вт", "ср", "чт", "пт", "сб"],
+        months: ["январь", "февраль", "март", "апрель", "май", "июнь", "июль", "август", "сентябрь", "октябрь", "ноябрь", "декабрь"],
+        months_in_date: Some(["января", "февраля", "марта", "апреля", "мая", "июня", "июля", "августа", "сентября", "октября", "ноября", "декабря"]),
+        months_abb: ["янв.", "февр.", "мар.", "апр.", "мая", "июн.", "июл.", "авг.", "сент.", "окт.", "нояб.", "дек."],
+        seasons: ["весна", "лето", "осень", "зима"],
+        season_day: "{season}, день {n}",
+        am: "AM",
+        pm: "PM",
+        hour12: false,
+        time12: "h:mm a",
+        ordinal: ordinal_ru,
+        relative: &RELATIVE_RU,
+    },
+    Locale {
+        tag: "pl-PL",
+        days: ["niedziela", "poniedziałek", "wtorek", "środa", "czwartek", "piątek", "sobota"],
+        days_abb: ["niedz.", "pon.", "wt.", "śr.", "czw.", "pt.", "sob."],
+        months: ["styczeń", "luty", "marzec", "kwiecień", "maj", "czerwiec", "lipiec", "sierpień", "wrzesień", "październik", "listopad", "grudzień"],
+        months_in_date: Some(["stycznia", "lutego", "marca", "kwietnia", "maja", "czerwca", "lipca", "sierpnia", "września", "października", "listopada", "grudnia"]),
+        months_abb: ["sty", "lut", "mar", "kwi", "maj", "cze", "lip", "sie", "wrz", "paź", "lis", "gru"],
+        seasons: ["wiosna", "lato", "jesień", "zima"],
+        season_day: "{season}, dzień {n}",
+        am: "AM",
+        pm: "PM",
+        hour12: false,
+        time12: "h:mm a",
+        ordinal: ordinal_dot,
+        relative: &RELATIVE_PL,
+    },
+    Locale {
+        tag: "ja-JP",
+        days: ["日曜日", "月曜日", "火曜日", "水曜日", "木曜日", "金曜日", "土曜日"],
+        days_abb: ["日", "月", "火", "水", "木", "金", "土"],
+        months: ["1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月", "10月", "11月", "12月"],
+        months_in_date: None,
+        months_abb: ["1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月", "10月", "11月", "12月"],
+        seasons: ["春", "夏", "秋", "冬"],
+        season_day: "{season}の{n}日目",
+        am: "午前",
+        pm: "午後",
+        hour12: false,
+        time12: "ah:mm",
+        ordinal: ordinal_cjk_day,
+        relative: &RELATIVE_JA,
+    },
+    Locale {
+        tag: "zh-CN",
+        days: ["星期日", "星期一", "星期二", "星期三", "星期四", "星期五", "星期六"],
+        days_abb: ["周日", "周一", "周二", "周三", "周四", "周五", "周六"],
+        months: ["一月", "二月", "三月", "四月", "五月", "六月", "七月", "八月", "九月", "十月", "十一月", "十二月"],
+        months_in_date: None,
+        months_abb: ["1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月", "10月", "11月", "12月"],
+        seasons: ["春季", "夏季", "秋季", "冬季"],
+        season_day: "{season}第{n}天",
+        am: "上午",
+        pm: "下午",
+        hour12: false,
+        time12: "ah:mm",
+        ordinal: ordinal_cjk_day,
+        relative: &RELATIVE_ZH,
+    },
+    Locale {
+        tag: "ko-KR",
+        days: ["일요일", "월요일", "화요일", "수요일", "목요일", "금요일", "토요일"],
+        days_abb: ["일", "월", "화", "수", "목", "금", "토"],
+        months: ["1월", "2월", "3월", "4월", "5월", "6월", "7월", "8월", "9월", "10월", "11월", "12월"],
+        months_in_date: None,
+        months_abb: ["1월", "2월", "3월", "4월", "5월", "6월", "7월", "8월", "9월", "10월", "11월", "12월"],
+        seasons: ["봄", "여름", "가을", "겨울"],
+        season_day: "{season} {n}일째",
+        am: "오전",
+        pm: "오후",
+        hour12: true,
+        time12: "a h:mm",
+        ordinal: ordinal_ko,
+        relative: &RELATIVE_KO,
+    },
+    Locale {
+        tag: "ar-SA",
+        days: ["الأحد", "الاثنين", "الثلاثاء", "الأربعاء", "الخميس", "الجمعة", "السبت"],
+        days_abb: ["الأحد", "الاثنين", "الثلاثاء", "الأربعاء", "الخميس", "الجمعة", "السبت"],
+        months: ["يناير", "فبراير", "مارس", "أبريل", "مايو", "يونيو", "يوليو", "أغسطس", "سبتمبر", "أكتوبر", "نوفمبر", "ديسمبر"],
+        months_in_date: None,
+        months_abb: ["يناير", "فبراير", "مارس", "أبريل", "مايو", "يونيو", "يوليو", "أغسطس", "سبتمبر", "أكتوبر", "نوفمبر", "ديسمبر"],
+        seasons: ["الربيع", "الصيف", "الخريف", "الشتاء"],
+        season_day: "{season}، اليوم {n}",
+        am: "ص",
+        pm: "م",
+        hour12: true,
+        time12: "h:mm a",
+        ordinal: ordinal_plain,
+        relative: &RELATIVE_AR,
+    },
+];
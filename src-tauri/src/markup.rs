@@ -0,0 +1,54 @@
+//! Keywords that wrap an instant for chat apps and markup: Discord and Slack
+//! date tags, HTML `<time>`, Org-mode timestamps and Markdown front matter.
+
+use chrono::{DateTime, Datelike, FixedOffset, Timelike};
+
+use crate::locale::Locale;
+
+/// Discord's `<t:UNIX:STYLE>` style letters.
+const DISCORD_STYLES: &str = "tTdDfFR";
+
+/// Keywords `render` handles besides `discord_<style>`.
+const KEYWORDS: &[&str] = &["discord", "slack", "html", "org", "org_inactive", "markdown"];
+
+/// Whether `render` knows `keyword`.
+pub fn is_keyword(keyword: &str) -> bool {
+    KEYWORDS.contains(&keyword) || discord_style(keyword).is_some()
+}
+
+/// The style letter of a `discord_<style>` keyword.
+fn discord_style(keyword: &str) -> Option<&str> {
+    keyword.strip_prefix("discord_").filter(|s| s.len() == 1 && DISCORD_STYLES.contains(*s))
+}
+
+/// `at` wrapped for `keyword`, or `None` if it isn't a markup keyword.
+/// Fallback and inner text is a short readable date in `locale`.
+pub fn render(keyword: &str, at: &DateTime<FixedOffset>, locale: &Locale) -> Option<String> {
+    let unix = at.timestamp();
+    Some(match keyword {
+        "discord" => format!("<t:{}:F>", unix),
+        "slack" => format!("<!date^{}^{{date_short}} {{time}}|{}>", unix, readable(at, locale)),
+        "html" => time_element(at, &readable(at, locale)),
+        "org" => at.format("<%Y-%m-%d %a %H:%M>").to_string(),
+        "org_inactive" => at.format("[%Y-%m-%d %a %H:%M]").to_string(),
+        "markdown" => format!("date: {}", at.to_rfc3339()),
+        _ => format!("<t:{}:{}>", unix, discord_style(keyword)?),
+    })
+}
+
+/// `text` in a `<time>` element carrying `at` as RFC 3339.
+pub fn time_element(at: &DateTime<FixedOffset>, text: &str) -> String {
+    let escaped = text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+    format!("<time datetime=\"{}\">{}</time>", at.to_rfc3339(), escaped)
+}
+
+/// "Oct. 17, 2026 1:45 PM" in en-US, matching Slack's `{date_short} {time}`.
+fn readable(at: &DateTime<FixedOffset>, locale: &Locale) -> String {
+    format!(
+        "{} {}, {} {}",
+        locale.months_abb[at.month0() as usize],
+        at.day(),
+        at.year(),
+        locale.clock(at.hour(), at.minute())
+    )
+}
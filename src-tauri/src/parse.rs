@@ -0,0 +1,570 @@
+//! Timestamp parsing and format auto-detection: the reverse of the token engine.
+//!
+//! Every detector that accepts the input contributes a candidate with a
+//! confidence in `0.0..=1.0`; the most confident one wins.
+
+use chrono::{DateTime, Datelike, FixedOffset, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::epochs::Epoch;
+use crate::error::TimestampError;
+use crate::locale::Locale;
+use crate::presets;
+use crate::template::{Node, Template, Token};
+use crate::timestamp::{from_julian_day, is_lenient, resolve_zone, stored_pref, InstantInput, MJD_EPOCH};
+use crate::zones;
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsedTimestamp {
+    /// Pass straight back to `format_instant` to re-render in any preset.
+    pub instant: InstantInput,
+    /// Identifier of the detected format, e.g. `unix-millis`, `rfc2822`,
+    /// `common-log` or `preset:Journal`.
+    pub format: String,
+    pub confidence: f32,
+}
+
+/// A preset to read back, in the zone and locale it renders in.
+pub struct PresetPattern {
+    pub name: String,
+    pub format: String,
+    pub tz: Tz,
+    pub locale: &'static Locale,
+}
+
+/// Detect the format of `input` and normalize it to UTC. Inputs without an
+/// offset are read in the zone picked by the usual policy (see `zones::resolve`).
+/// Many epoch encodings overlap in magnitude, so `encoding` skips detection
+/// and reads `input` as that one.
+#[tauri::command]
+pub fn parse_timestamp(
+    app: AppHandle,
+    input: String,
+    timezone: Option<String>,
+    encoding: Option<Epoch>,
+) -> Result<ParsedTimestamp, TimestampError> {
+    let lenient = is_lenient(&app, None);
+    let (tz, _) = resolve_zone(&app, timezone.clone(), lenient)?;
+    let invalid = |message: String| TimestampError::InvalidInstant { message };
+    if let Some(epoch) = encoding {
+        return epoch
+            .decode(&input, tz)
+            .map(|at| ParsedTimestamp { instant: InstantInput::Utc(at), format: epoch_format(epoch).into(), confidence: 1.0 })
+            .ok_or_else(|| invalid(format!("Not a valid {} timestamp: '{}'", epoch_format(epoch), input.trim())));
+    }
+    let locale = Locale::resolve(stored_pref::<String>(&app, "locale").as_deref().unwrap_or("en-US"));
+    let mut patterns = Vec::new();
+    if let Some(format) = stored_pref::<String>(&app, "defaultFormat") {
+        patterns.push(PresetPattern { name: "Default Format".into(), format, tz, locale });
+    }
+    // Presets whose zone no longer resolves can't have rendered the input.
+    for preset in presets::load(&app) {
+        let Ok((preset_tz, _)) = resolve_zone(&app, preset.timezone.or_else(|| timezone.clone()), lenient) else { continue };
+        patterns.push(PresetPattern {
+            name: preset.name,
+            format: preset.format,
+            tz: preset_tz,
+            locale: preset.locale.as_deref().map_or(locale, Locale::resolve),
+        });
+    }
+    detect(&input, tz, Utc::now(), &patterns)
+        .ok_or_else(|| invalid(format!("Unrecognized timestamp: '{}'", input.trim())))
+}
+
+pub fn detect(
+    input: &str,
+    tz: Tz,
+    now: DateTime<Utc>,
+    presets: &[PresetPattern],
+) -> Option<ParsedTimestamp> {
+    let input = input.trim();
+    if input.is_empty() {
+        return None;
+    }
+    let mut candidates: Vec<(DateTime<Utc>, String, f32)> = Vec::new();
+    let mut push = |at: Option<DateTime<Utc>>, format: &str, confidence: f32| {
+        if let Some(at) = at {
+            candidates.push((at, format.to_string(), confidence));
+        }
+    };
+
+    for (at, format, confidence) in detect_epoch(input) {
+        push(Some(at), format, confidence);
+    }
+    for (at, format, confidence) in detect_platform_epoch(input, tz) {
+        push(Some(at), format, confidence);
+    }
+
+    push(DateTime::parse_from_rfc3339(input).ok().map(|d| d.with_timezone(&Utc)), "rfc3339", 1.0);
+    push(parse_fixed(input, "%Y%m%dT%H%M%S%#z"), "iso8601-basic", 0.95);
+    push(parse_fixed(input, "%Y-%m-%dT%H:%M%#z"), "iso8601", 0.95);
+
+    // HTTP-date is the IMF-fixdate subset of RFC 2822, always in GMT.
+    if input.ends_with(" GMT") {
+        push(parse_naive(input, "%a, %d %b %Y %H:%M:%S GMT", Tz::UTC), "http-date", 1.0);
+        push(parse_naive(input, "%A, %d-%b-%y %H:%M:%S GMT", Tz::UTC), "rfc850", 0.9);
+    }
+    push(DateTime::parse_from_rfc2822(input).ok().map(|d| d.with_timezone(&Utc)), "rfc2822", 0.95);
+    push(parse_naive(input, "%a %b %e %H:%M:%S %Y", Tz::UTC), "asctime", 0.9);
+
+    push(parse_fixed(input.trim_matches(['[', ']']), "%d/%b/%Y:%H:%M:%S %z"), "common-log", 0.95);
+    push(parse_naive(input, "%Y/%m/%d %H:%M:%S", tz), "nginx-log", 0.8);
+    push(parse_syslog(input, tz, now), "syslog", 0.6);
+
+    // Keyword presets (`datetime`, `date`, `time`) and offset-less ISO 8601.
+    push(parse_naive(input, "%Y-%m-%d %H:%M:%S", tz), "datetime", 0.85);
+    push(parse_naive(input, "%Y-%m-%dT%H:%M:%S%.f", tz), "iso8601-local", 0.8);
+    push(parse_naive(input, "%Y-%m-%dT%H:%M", tz), "iso8601-local", 0.75);
+    push(parse_date(input, "%Y-%m-%d", tz), "date", 0.85);
+    if input.len() == 8 {
+        push(parse_date(input, "%Y%m%d", tz), "iso8601-basic-date", 0.7);
+    }
+    push(parse_time(input, tz, now), "time", 0.5);
+
+    for p in presets {
+        if let Some((at, confidence)) = match_template(&Template::parse(&p.format), input, p.tz, now, p.locale) {
+            push(Some(at), &format!("preset:{}", p.name), confidence);
+        }
+    }
+
+    candidates
+        .into_iter()
+        .max_by(|a, b| a.2.total_cmp(&b.2))
+        .map(|(at, format, confidence)| ParsedTimestamp { instant: InstantInput::Utc(at), format, confidence })
+}
+
+/// Integers are classified by magnitude; plausible years score higher.
+fn detect_epoch(input: &str) -> Vec<(DateTime<Utc>, &'static str, f32)> {
+    let plausible = |at: &DateTime<Utc>| (1971..=2100).contains(&at.year());
+    let score = |at: DateTime<Utc>, format, high, low| (at, format, if plausible(&at) { high } else { low });
+
+    if let Some((whole, frac)) = input.split_once('.') {
+        if is_integer(whole) && !frac.is_empty() && frac.bytes().all(|b| b.is_ascii_digit()) {
+            let nanos: u32 = format!("{:0<9}", &frac[..frac.len().min(9)]).parse().ok().unwrap_or(0);
+            // "-1.5" is half a second after -2, and "-0.5" has no sign left once parsed.
+            let negative = whole.starts_with('-');
+            return whole
+                .parse::<i64>()
+                .ok()
+                .and_then(|s| {
+                    if negative && nanos > 0 {
+                        DateTime::from_timestamp(s.checked_sub(1)?, 1_000_000_000 - nanos)
+                    } else {
+                        DateTime::from_timestamp(s, nanos)
+                    }
+                })
+                .map(|at| vec![score(at, "unix-seconds", 0.9, 0.4)])
+                .unwrap_or_default();
+        }
+        return Vec::new();
+    }
+    if !is_integer(input) {
+        return Vec::new();
+    }
+    let Ok(n) = input.parse::<i128>() else { return Vec::new() };
+    let magnitude = n.unsigned_abs();
+    let at = if magnitude < 100_000_000_000 {
+        i64::try_from(n).ok().and_then(|s| DateTime::from_timestamp(s, 0)).map(|at| (at, "unix-seconds"))
+    } else if magnitude < 100_000_000_000_000 {
+        i64::try_from(n).ok().and_then(DateTime::from_timestamp_millis).map(|at| (at, "unix-millis"))
+    } else if magnitude < 100_000_000_000_000_000 {
+        i64::try_from(n).ok().and_then(DateTime::from_timestamp_micros).map(|at| (at, "unix-micros"))
+    } else {
+        i64::try_from(n).ok().map(|ns| (DateTime::from_timestamp_nanos(ns), "unix-nanos"))
+    };
+    at.map(|(at, format)| vec![score(at, format, 0.9, 0.5)]).unwrap_or_default()
+}
+
+/// Encodings recognizable by shape or by a magnitude that plain Unix values
+/// don't plausibly have. NTP, GPS and Cocoa seconds look like Unix seconds,
+/// so they are only read with an explicit `encoding`.
+fn detect_platform_epoch(input: &str, tz: Tz) -> Vec<(DateTime<Utc>, &'static str, f32)> {
+    let plausible = |at: &DateTime<Utc>| (1971..=2100).contains(&at.year());
+    let hex = input.strip_prefix("0x").or_else(|| input.strip_prefix("0X"));
+    let digits = hex.unwrap_or(input);
+    let is_hex = !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_hexdigit());
+    let mut out = Vec::new();
+    let mut try_epoch = |epoch: Epoch, confidence: f32| {
+        if let Some(at) = epoch.decode(input, tz).filter(plausible) {
+            out.push((at, epoch_format(epoch), confidence));
+        }
+    };
+    if is_hex && digits.len() == 16 {
+        try_epoch(Epoch::Filetime, 0.9);
+    } else if is_hex && (hex.is_some() || (digits.len() == 8 && !is_integer(digits))) {
+        try_epoch(Epoch::UnixHex, 0.8);
+    } else if input.len() == 18 && is_integer(input) {
+        // 2000–2100 is 1.26e17–1.58e17 as LDAP and 6.3e17–6.6e17 as ticks,
+        // both where Unix nanoseconds would be the 1970s.
+        try_epoch(Epoch::Ldap, 0.95);
+        try_epoch(Epoch::DotnetTicks, 0.95);
+    } else if input.split('.').next().is_some_and(|whole| whole.len() == 5 && is_integer(whole)) {
+        // 25569–73050 is 1970–2099 as a serial and early 1970 as Unix seconds.
+        try_epoch(Epoch::Excel, 0.7);
+    }
+    out
+}
+
+/// Format identifier reported for an epoch encoding.
+fn epoch_format(epoch: Epoch) -> &'static str {
+    match epoch {
+        Epoch::UnixSeconds => "unix-seconds",
+        Epoch::UnixMillis => "unix-millis",
+        Epoch::UnixMicros => "unix-micros",
+        Epoch::UnixNanos => "unix-nanos",
+        Epoch::UnixHex => "unix-hex",
+        Epoch::Filetime => "filetime",
+        Epoch::DotnetTicks => "dotnet-ticks",
+        Epoch::Cocoa => "cocoa",
+        Epoch::Ldap => "ldap",
+        Epoch::Ntp => "ntp",
+        Epoch::Gps => "gps",
+        Epoch::Excel => "excel",
+    }
+}
+
+fn is_integer(s: &str) -> bool {
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+pub(crate) fn local_to_utc(naive: NaiveDateTime, tz: Tz) -> Option<DateTime<Utc>> {
+    tz.from_local_datetime(&naive).earliest().map(|d| d.with_timezone(&Utc))
+}
+
+fn parse_fixed(input: &str, fmt: &str) -> Option<DateTime<Utc>> {
+    DateTime::<FixedOffset>::parse_from_str(input, fmt).ok().map(|d| d.with_timezone(&Utc))
+}
+
+fn parse_naive(input: &str, fmt: &str, tz: Tz) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(input, fmt).ok().and_then(|n| local_to_utc(n, tz))
+}
+
+fn parse_date(input: &str, fmt: &str, tz: Tz) -> Option<DateTime<Utc>> {
+    NaiveDate::parse_from_str(input, fmt).ok().and_then(|d| local_to_utc(d.and_time(NaiveTime::MIN), tz))
+}
+
+/// A bare `HH:MM:SS` is taken to be today.
+fn parse_time(input: &str, tz: Tz, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let time = NaiveTime::parse_from_str(input, "%H:%M:%S").ok()?;
+    local_to_utc(now.with_timezone(&tz).date_naive().and_time(time), tz)
+}
+
+/// `Oct 10 13:55:36` carries no year; assume the current one.
+fn parse_syslog(input: &str, tz: Tz, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let year = now.with_timezone(&tz).year();
+    let with_year = format!("{} {}", year, input);
+    parse_naive(&with_year, "%Y %b %e %H:%M:%S", tz)
+}
+
+/// Fields collected while matching a template against text.
+#[derive(Default)]
+struct Fields {
+    year: Option<i32>,
+    month: Option<u32>,
+    day: Option<u32>,
+    /// Day of the year, used when there is no month and day.
+    ordinal: Option<u32>,
+    julian_day: Option<u32>,
+    hour: Option<u32>,
+    minute: Option<u32>,
+    second: Option<u32>,
+    millis: Option<u32>,
+    pm: Option<bool>,
+    offset: Option<FixedOffset>,
+    zone: Option<Tz>,
+    /// A `{timezone}` abbreviation such as "EDT" or "+04".
+    abbreviation: Option<String>,
+}
+
+/// Read one of our own rendered presets back, e.g. "Tuesday, November 14 5:13 PM".
+/// Returns `None` if the template uses tokens that cannot be read back.
+fn match_template(template: &Template, input: &str, tz: Tz, now: DateTime<Utc>, locale: &Locale) -> Option<(DateTime<Utc>, f32)> {
+    if !template.diagnostics.is_empty() || template.nodes.is_empty() {
+        return None;
+    }
+    let mut rest = input;
+    let mut f = Fields::default();
+    for node in &template.nodes {
+        match node {
+            Node::Literal(lit) => rest = strip_prefix_ci(rest, lit)?,
+            // Matching ignores case, but widths changed by padding or abbreviation
+            // can't be read back, nor can fields rendered in another zone.
+            Node::Token(p) if p.zone.is_none() && p.calendar.is_none() && p.modifiers.iter().all(|m| m.is_case()) => rest = match_token(p.token, rest, locale, &mut f)?,
+            Node::Token(_) | Node::Strftime { .. } => return None,
+        }
+    }
+    if !rest.is_empty() {
+        return None;
+    }
+
+    let year_known = f.year.is_some() || f.julian_day.is_some();
+    let zone = f.zone.unwrap_or(tz);
+    let year = f.year.unwrap_or_else(|| now.with_timezone(&zone).year());
+    let date = match (f.julian_day, f.month, f.day) {
+        (Some(jdn), _, _) => from_julian_day(i64::from(jdn))?,
+        (None, Some(month), Some(day)) => NaiveDate::from_ymd_opt(year, month, day)?,
+        _ => NaiveDate::from_yo_opt(year, f.ordinal?)?,
+    };
+    let mut hour = f.hour.unwrap_or(0);
+    if let Some(pm) = f.pm {
+        hour = hour % 12 + if pm { 12 } else { 0 };
+    }
+    let naive = date.and_hms_milli_opt(hour, f.minute.unwrap_or(0), f.second.unwrap_or(0), f.millis.unwrap_or(0))?;
+    let at = match (f.offset, f.abbreviation) {
+        (Some(offset), _) => offset.from_local_datetime(&naive).single()?.with_timezone(&Utc),
+        (None, Some(abbr)) => abbreviated_to_utc(naive, zone, &abbr)?,
+        (None, None) => local_to_utc(naive, zone)?,
+    };
+    Some((at, if year_known { 0.85 } else { 0.6 }))
+}
+
+/// `naive` read with the offset `abbr` names. Named abbreviations are only
+/// unambiguous within a zone, so they must be one `zone` uses at that wall
+/// time, which also picks the side of a DST fold ("01:30 EDT" vs "01:30 EST").
+/// Numeric ones ("+04", "-0330") and UTC/GMT stand for themselves.
+fn abbreviated_to_utc(naive: NaiveDateTime, zone: Tz, abbr: &str) -> Option<DateTime<Utc>> {
+    let in_zone = match zone.from_local_datetime(&naive) {
+        LocalResult::Single(a) => vec![a],
+        LocalResult::Ambiguous(a, b) => vec![a, b],
+        LocalResult::None => Vec::new(),
+    };
+    if let Some(at) = in_zone.into_iter().find(|at| zones::abbreviation(at).eq_ignore_ascii_case(abbr)) {
+        return Some(at.with_timezone(&Utc));
+    }
+    let offset = if abbr.eq_ignore_ascii_case("UTC") || abbr.eq_ignore_ascii_case("GMT") {
+        FixedOffset::east_opt(0)?
+    } else {
+        numeric_offset(abbr)?
+    };
+    offset.from_local_datetime(&naive).single().map(|at| at.with_timezone(&Utc))
+}
+
+/// "+04", "-03" or "+0530", as tz abbreviations write offsets.
+fn numeric_offset(abbr: &str) -> Option<FixedOffset> {
+    let sign = match abbr.as_bytes().first()? { b'+' => 1, b'-' => -1, _ => return None };
+    let digits = &abbr[1..];
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let (h, m): (i32, i32) = match digits.len() {
+        2 => (digits.parse().ok()?, 0),
+        4 => (digits[..2].parse().ok()?, digits[2..].parse().ok()?),
+        _ => return None,
+    };
+    FixedOffset::east_opt(sign * (h * 3600 + m * 60))
+}
+
+fn match_token<'a>(token: Token, s: &'a str, locale: &Locale, f: &mut Fields) -> Option<&'a str> {
+    Some(match token {
+        Token::Day => match_name(s, &locale.days)?.1,
+        Token::DayAbb => match_name(s, &locale.days_abb)?.1,
+        Token::Month => {
+            // Either form, whichever is longer: "марта" would otherwise stop at "март".
+            let names: Vec<&str> = locale.months.iter().chain(locale.months_in_date.iter().flatten()).copied().collect();
+            let (i, rest) = match_name(s, &names)?;
+            f.month = Some(i as u32 % 12 + 1);
+            rest
+        }
+        Token::MonthAbb => {
+            let (i, rest) = match_name(s, &locale.months_abb)?;
+            f.month = Some(i as u32 + 1);
+            rest
+        }
+        Token::Date => digits(s, 1, 2, &mut f.day)?,
+        Token::DayOrdinal => {
+            let rest = digits(s, 1, 2, &mut f.day)?;
+            let suffix = locale.ordinal(f.day?);
+            strip_prefix_ci(rest, suffix.trim_start_matches(|c: char| c.is_ascii_digit()))?
+        }
+        Token::Year => {
+            let mut y = None;
+            let rest = digits(s, 4, 4, &mut y)?;
+            f.year = y.map(|y| y as i32);
+            rest
+        }
+        Token::YearShort => {
+            let mut y = None;
+            let rest = digits(s, 2, 2, &mut y)?;
+            f.year = y.map(|y| 2000 + y as i32);
+            rest
+        }
+        Token::MonthNum => digits(s, 1, 2, &mut f.month)?,
+        Token::MonthNumPad => digits(s, 2, 2, &mut f.month)?,
+        Token::Time => {
+            let rest = digits(s, 1, 2, &mut f.hour)?;
+            digits(rest.strip_prefix(':')?, 2, 2, &mut f.minute)?
+        }
+        Token::Time24 => {
+            let rest = digits(s, 2, 2, &mut f.hour)?;
+            digits(rest.strip_prefix(':')?, 2, 2, &mut f.minute)?
+        }
+        Token::Hours | Token::Hours24 => digits(s, 1, 2, &mut f.hour)?,
+        Token::Minutes => digits(s, 2, 2, &mut f.minute)?,
+        Token::Seconds => digits(s, 2, 2, &mut f.second)?,
+        Token::Milliseconds => digits(s, 3, 3, &mut f.millis)?,
+        Token::Period => {
+            let (i, rest) = match_name(s, &[locale.am, locale.pm])?;
+            f.pm = Some(i == 1);
+            rest
+        }
+        Token::Week
+        | Token::IsoWeek
+        | Token::WeekOfMonth
+        | Token::Quarter
+        | Token::FiscalQuarter
+        | Token::FiscalPeriod
+        | Token::FiscalWeek => digits(s, 1, 2, &mut None)?,
+        Token::IsoWeekYear | Token::FiscalYear => digits(s, 4, 4, &mut None)?,
+        Token::DayOfYear => digits(s, 1, 3, &mut f.ordinal)?,
+        Token::DaysLeftInYear => digits(s, 1, 3, &mut None)?,
+        Token::IsoOrdinal => {
+            let mut y = None;
+            let rest = digits(s, 4, 4, &mut y)?;
+            f.year = y.map(|y| y as i32);
+            digits(rest.strip_prefix('-')?, 3, 3, &mut f.ordinal)?
+        }
+        Token::JulianDay => digits(s, 1, 7, &mut f.julian_day)?,
+        Token::ModifiedJulianDay => {
+            let rest = digits(s, 1, 6, &mut f.julian_day)?;
+            f.julian_day = f.julian_day.map(|mjd| mjd + MJD_EPOCH as u32);
+            rest
+        }
+        Token::Era => match_name(s, &["AD"])?.1,
+        Token::Season => match_name(s, &locale.seasons)?.1,
+        Token::UtcOffset => {
+            let sign = match s.as_bytes().first()? { b'+' => 1, b'-' => -1, _ => return None };
+            let (mut h, mut m) = (None, None);
+            let rest = digits(&s[1..], 2, 2, &mut h)?;
+            let rest = digits(rest.strip_prefix(':')?, 2, 2, &mut m)?;
+            f.offset = FixedOffset::east_opt(sign * (h? * 3600 + m? * 60) as i32);
+            rest
+        }
+        Token::TimezoneFull => {
+            let end = s.find(|c: char| !(c.is_ascii_alphanumeric() || "/_+-".contains(c))).unwrap_or(s.len());
+            f.zone = Some(s[..end].parse().ok()?);
+            &s[end..]
+        }
+        Token::Timezone => {
+            let end = s.find(|c: char| !(c.is_ascii_alphanumeric() || "+-".contains(c))).unwrap_or(s.len());
+            if end == 0 {
+                return None;
+            }
+            f.abbreviation = Some(s[..end].to_string());
+            &s[end..]
+        }
+        Token::TimeLocale | Token::TimezoneLong | Token::Relative | Token::SeasonDay | Token::Epoch(_) | Token::Id(_) => return None,
+    })
+}
+
+/// Longest case-insensitive match among `names`, returning its index.
+fn match_name<'a>(s: &'a str, names: &[&str]) -> Option<(usize, &'a str)> {
+    names
+        .iter()
+        .enumerate()
+        .filter_map(|(i, name)| strip_prefix_ci(s, name).map(|rest| (i, name.len(), rest)))
+        .max_by_key(|(_, len, _)| *len)
+        .map(|(i, _, rest)| (i, rest))
+}
+
+fn strip_prefix_ci<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = s.get(..prefix.len())?;
+    (head == prefix || head.to_lowercase() == prefix.to_lowercase()).then_some(&s[prefix.len()..])
+}
+
+fn digits<'a>(s: &'a str, min: usize, max: usize, out: &mut Option<u32>) -> Option<&'a str> {
+    let len = s.bytes().take(max).take_while(|b| b.is_ascii_digit()).count();
+    if len < min {
+        return None;
+    }
+    *out = Some(s[..len].parse().ok()?);
+    Some(&s[len..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(input: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(input).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn negative_fractional_seconds() {
+        let read = |input: &str| detect_epoch(input).first().map(|c| c.0);
+        assert_eq!(read("-1.5"), Some(utc("1969-12-31T23:59:58.500Z")));
+        assert_eq!(read("-0.25"), Some(utc("1969-12-31T23:59:59.750Z")));
+        assert_eq!(read("-2.0"), Some(utc("1969-12-31T23:59:58Z")));
+        assert_eq!(read("1.5"), Some(utc("1970-01-01T00:00:01.500Z")));
+    }
+
+    #[test]
+    fn timezone_abbreviations_set_the_offset() {
+        let now = utc("2024-06-01T00:00:00Z");
+        let locale = Locale::resolve("en-US");
+        let template = Template::parse("{year}-{month-num-pad}-{date} {time24} {timezone}");
+        let read = |input: &str, tz: Tz| match_template(&template, input, tz, now, locale).map(|m| m.0);
+        let ny = Tz::America__New_York;
+        // 01:30 happens twice on 2024-11-03 in New York.
+        assert_eq!(read("2024-11-03 01:30 EDT", ny), Some(utc("2024-11-03T05:30:00Z")));
+        assert_eq!(read("2024-11-03 01:30 EST", ny), Some(utc("2024-11-03T06:30:00Z")));
+        assert_eq!(read("2024-07-01 12:00 +04", ny), Some(utc("2024-07-01T08:00:00Z")));
+        assert_eq!(read("2024-07-01 12:00 UTC", ny), Some(utc("2024-07-01T12:00:00Z")));
+        // Another zone's name can't be read without knowing which zone.
+        assert_eq!(read("2024-07-01 12:00 JST", ny), None);
+    }
+
+    #[test]
+    fn presets_match_in_their_own_zone_and_locale() {
+        let now = Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap();
+        let preset = PresetPattern {
+            name: "Tokyo".into(),
+            format: "{date}. {month} {year} {time24}".into(),
+            tz: Tz::Asia__Tokyo,
+            locale: Locale::resolve("de-DE"),
+        };
+        let parsed = detect("1. März 2024 09:00", Tz::UTC, now, &[preset]).unwrap();
+        assert_eq!(parsed.format, "preset:Tokyo");
+        assert_eq!(parsed.instant.resolve().unwrap(), Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap());
+    }
+
+    fn read(input: &str) -> Option<(String, DateTime<Utc>)> {
+        let parsed = detect(input, Tz::UTC, utc("2024-06-01T00:00:00Z"), &[])?;
+        Some((parsed.format, parsed.instant.resolve().unwrap()))
+    }
+
+    #[test]
+    fn epochs_by_magnitude() {
+        let expect = |format: &str, at: &str| Some((format.to_string(), utc(at)));
+        assert_eq!(read("1700000000"), expect("unix-seconds", "2023-11-14T22:13:20Z"));
+        assert_eq!(read("1700000000123"), expect("unix-millis", "2023-11-14T22:13:20.123Z"));
+        assert_eq!(read("1700000000123456"), expect("unix-micros", "2023-11-14T22:13:20.123456Z"));
+        assert_eq!(read("1700000000123456789"), expect("unix-nanos", "2023-11-14T22:13:20.123456789Z"));
+        assert_eq!(read("-86400"), expect("unix-seconds", "1969-12-31T00:00:00Z"));
+        // Implausible years still parse, with less confidence.
+        let old = detect("86400", Tz::UTC, Utc::now(), &[]).unwrap();
+        assert_eq!((old.format.as_str(), old.confidence), ("unix-seconds", 0.5));
+        assert_eq!(detect("1700000000", Tz::UTC, Utc::now(), &[]).unwrap().confidence, 0.9);
+    }
+
+    #[test]
+    fn rfc_2822_and_http_dates() {
+        let expect = |format: &str, at: &str| Some((format.to_string(), utc(at)));
+        assert_eq!(read("Tue, 14 Nov 2023 22:13:20 +0100"), expect("rfc2822", "2023-11-14T21:13:20Z"));
+        assert_eq!(read("14 Nov 2023 22:13:20 -0500"), expect("rfc2822", "2023-11-15T03:13:20Z"));
+        assert_eq!(read("Tue, 14 Nov 2023 22:13:20 GMT"), expect("http-date", "2023-11-14T22:13:20Z"));
+        assert_eq!(read("Tuesday, 14-Nov-23 22:13:20 GMT"), expect("rfc850", "2023-11-14T22:13:20Z"));
+        assert_eq!(read("Tue Nov 14 22:13:20 2023"), expect("asctime", "2023-11-14T22:13:20Z"));
+    }
+
+    #[test]
+    fn common_log_format() {
+        let expect = Some(("common-log".to_string(), utc("2023-11-14T21:13:20Z")));
+        assert_eq!(read("[14/Nov/2023:22:13:20 +0100]"), expect);
+        assert_eq!(read("14/Nov/2023:22:13:20 +0100"), expect);
+        assert_eq!(read("[14/Nov/2023:22:13:20]"), None);
+    }
+}
@@ -0,0 +1,316 @@
+//! The preset registry: named formats shown in the widget, with optional
+//! per-preset zone and locale.
+//!
+//! Presets are kept under `presets` in `prefs.json`, where the preferences
+//! page used to write bare `{ name, format }` objects; those still load, and
+//! are given ids by `migrate` at startup. Entries that don't read as presets
+//! are left out of the list but kept in the store, and the commands refuse
+//! to save over a `presets` value that isn't a list at all.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_store::StoreExt;
+
+use crate::clipboard::CopyAs;
+use crate::error::TimestampError;
+use crate::ids::{IdKind, SnowflakeConfig};
+use crate::locale::Locale;
+use crate::timestamp::{is_keyword, stored_pref, FormatCache};
+use crate::zones;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Preset {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    /// A keyword such as `iso`, or a token format.
+    pub format: String,
+    /// Rendered in this zone instead of the usual one.
+    #[serde(default)]
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub locale: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub pinned: bool,
+    /// Position in the widget, from 0.
+    #[serde(default)]
+    pub sort_order: u32,
+    #[serde(default)]
+    pub copy_as: CopyAs,
+}
+
+/// The editable fields of a preset, for `create_preset` and `update_preset`.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetInput {
+    pub name: String,
+    pub format: String,
+    #[serde(default)]
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub locale: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub copy_as: CopyAs,
+}
+
+impl Preset {
+    fn new(id: String, sort_order: u32, input: PresetInput) -> Preset {
+        Preset {
+            id,
+            name: input.name,
+            format: input.format,
+            timezone: input.timezone,
+            locale: input.locale,
+            tags: input.tags,
+            pinned: input.pinned,
+            sort_order,
+            copy_as: input.copy_as,
+        }
+    }
+}
+
+/// The `presets` value as read: the entries that are presets, in widget
+/// order, and the ones that aren't, kept verbatim for the next save.
+#[derive(Default)]
+struct Stored {
+    presets: Vec<Preset>,
+    unreadable: Vec<Value>,
+}
+
+impl Stored {
+    fn read(value: Option<Value>) -> Result<Stored, TimestampError> {
+        let items = match value {
+            None | Some(Value::Null) => return Ok(Stored::default()),
+            Some(Value::Array(items)) => items,
+            Some(_) => return Err(invalid("The stored presets aren't a list, so they can't be edited here".into())),
+        };
+        let mut stored = Stored::default();
+        for item in items {
+            match serde_json::from_value(item.clone()) {
+                Ok(preset) => stored.presets.push(preset),
+                Err(e) => {
+                    eprintln!("Skipping unreadable preset: {}", e);
+                    stored.unreadable.push(item);
+                }
+            }
+        }
+        stored.presets.sort_by_key(|p| p.sort_order);
+        Ok(stored)
+    }
+
+    /// Give presets saved without an id one. Returns whether any were missing.
+    fn assign_ids(&mut self) -> bool {
+        let mut assigned = false;
+        for p in self.presets.iter_mut().filter(|p| p.id.is_empty()) {
+            p.id = new_id();
+            assigned = true;
+        }
+        assigned
+    }
+}
+
+fn read(app: &AppHandle) -> Result<Stored, TimestampError> {
+    Stored::read(stored_pref(app, "presets"))
+}
+
+/// Stored presets in widget order. Never writes; see `migrate`.
+pub(crate) fn load(app: &AppHandle) -> Vec<Preset> {
+    read(app).map(|s| s.presets).unwrap_or_default()
+}
+
+/// The stored presets for a command that will save them, with ids for any
+/// that `migrate` didn't get to.
+fn load_for_write(app: &AppHandle) -> Result<Stored, TimestampError> {
+    let mut stored = read(app)?;
+    stored.assign_ids();
+    Ok(stored)
+}
+
+/// Give presets written by older versions an id, once, at startup.
+pub fn migrate(app: &AppHandle) {
+    if let Ok(mut stored) = read(app) {
+        if stored.assign_ids() {
+            if let Err(e) = save(app, &mut stored) {
+                eprintln!("Failed to migrate presets: {}", e);
+            }
+        }
+    }
+}
+
+fn new_id() -> String {
+    IdKind::Ulid.generate(Utc::now(), &SnowflakeConfig::default())
+}
+
+/// Write the presets back in their current order, followed by the entries
+/// that couldn't be read.
+fn save(app: &AppHandle, stored: &mut Stored) -> Result<(), TimestampError> {
+    for (i, p) in stored.presets.iter_mut().enumerate() {
+        p.sort_order = i as u32;
+    }
+    let store = app.store("prefs.json").map_err(|e| invalid(e.to_string()))?;
+    let mut items = Vec::with_capacity(stored.presets.len() + stored.unreadable.len());
+    for p in &stored.presets {
+        items.push(serde_json::to_value(p).map_err(|e| invalid(e.to_string()))?);
+    }
+    items.extend(stored.unreadable.iter().cloned());
+    store.set("presets", Value::Array(items));
+    Ok(())
+}
+
+fn invalid(message: String) -> TimestampError {
+    TimestampError::InvalidSetting { name: "preset".into(), message }
+}
+
+fn position(presets: &[Preset], id: &str) -> Result<usize, TimestampError> {
+    presets.iter().position(|p| p.id == id).ok_or_else(|| invalid(format!("No preset with id '{}'", id)))
+}
+
+/// Check `input` with the token engine, strictly, and tidy its fields.
+/// Names must be unique, since parsing and copying look presets up by name.
+fn validate(app: &AppHandle, mut input: PresetInput, others: &[Preset]) -> Result<PresetInput, TimestampError> {
+    input.name = input.name.trim().to_string();
+    if input.name.is_empty() {
+        return Err(invalid("Name is required".into()));
+    }
+    if others.iter().any(|p| p.name.eq_ignore_ascii_case(&input.name)) {
+        return Err(invalid(format!("A preset named '{}' already exists", input.name)));
+    }
+    if !is_keyword(&input.format) {
+        app.state::<FormatCache>().get_checked(&input.format, false)?;
+    }
+    input.timezone = input.timezone.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+    if let Some(tz) = &input.timezone {
+        zones::lookup(tz)?;
+    }
+    input.locale = input.locale.map(|l| l.trim().to_string()).filter(|l| !l.is_empty());
+    if let Some(locale) = &input.locale {
+        if Locale::lookup(locale).is_none() {
+            return Err(invalid(format!("Unknown locale '{}'", locale)));
+        }
+    }
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input.tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+        if !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    input.tags = tags;
+    Ok(input)
+}
+
+#[tauri::command]
+pub fn list_presets(app: AppHandle) -> Vec<Preset> {
+    load(&app)
+}
+
+/// Add a preset at the end of the list.
+#[tauri::command]
+pub fn create_preset(app: AppHandle, preset: PresetInput) -> Result<Preset, TimestampError> {
+    let mut stored = load_for_write(&app)?;
+    let input = validate(&app, preset, &stored.presets)?;
+    let created = Preset::new(new_id(), stored.presets.len() as u32, input);
+    stored.presets.push(created.clone());
+    save(&app, &mut stored)?;
+    Ok(created)
+}
+
+/// Replace the editable fields of preset `id`, keeping its place.
+#[tauri::command]
+pub fn update_preset(app: AppHandle, id: String, preset: PresetInput) -> Result<Preset, TimestampError> {
+    let mut stored = load_for_write(&app)?;
+    let index = position(&stored.presets, &id)?;
+    let others: Vec<Preset> = stored.presets.iter().filter(|p| p.id != id).cloned().collect();
+    let input = validate(&app, preset, &others)?;
+    let updated = Preset::new(id, stored.presets[index].sort_order, input);
+    stored.presets[index] = updated.clone();
+    save(&app, &mut stored)?;
+    Ok(updated)
+}
+
+#[tauri::command]
+pub fn delete_preset(app: AppHandle, id: String) -> Result<(), TimestampError> {
+    let mut stored = load_for_write(&app)?;
+    stored.presets.remove(position(&stored.presets, &id)?);
+    save(&app, &mut stored)
+}
+
+/// Put the presets in `ids` first, in that order; any left out keep their
+/// relative order after them.
+#[tauri::command]
+pub fn reorder_presets(app: AppHandle, ids: Vec<String>) -> Result<Vec<Preset>, TimestampError> {
+    let mut stored = load_for_write(&app)?;
+    let mut ordered = Vec::with_capacity(stored.presets.len());
+    for id in &ids {
+        let index = position(&stored.presets, id)?;
+        ordered.push(stored.presets.remove(index));
+    }
+    ordered.append(&mut stored.presets);
+    stored.presets = ordered;
+    save(&app, &mut stored)?;
+    Ok(stored.presets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn unreadable_entries_are_kept_apart() {
+        let stored = Stored::read(Some(json!([
+            { "id": "b", "name": "Second", "format": "iso", "sortOrder": 1 },
+            { "name": "Legacy", "format": "{date}" },
+            { "name": 42 },
+            "not a preset",
+        ])))
+        .unwrap();
+        let names: Vec<&str> = stored.presets.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Legacy", "Second"]);
+        assert_eq!(stored.unreadable, [json!({ "name": 42 }), json!("not a preset")]);
+    }
+
+    #[test]
+    fn only_lists_can_be_saved_over() {
+        assert!(Stored::read(None).unwrap().presets.is_empty());
+        assert!(Stored::read(Some(Value::Null)).unwrap().presets.is_empty());
+        assert!(Stored::read(Some(json!({ "name": "x" }))).is_err());
+    }
+
+    #[test]
+    fn keywords_are_recognised_without_rendering() {
+        for keyword in ["iso", "datetime", "unix_ms", "ulid", "discord", "discord_R", "org_inactive"] {
+            assert!(is_keyword(keyword), "{}", keyword);
+        }
+        for format in ["{iso}", "discord_X", "ISO", ""] {
+            assert!(!is_keyword(format), "{}", format);
+        }
+    }
+
+    #[test]
+    fn locales_must_be_in_the_table() {
+        assert_eq!(Locale::lookup("de_AT").map(|l| l.tag), Some("de-DE"));
+        assert!(Locale::lookup("xx-YY").is_none());
+    }
+
+    #[test]
+    fn missing_ids_are_assigned_once() {
+        let mut stored = Stored::read(Some(json!([
+            { "id": "a", "name": "Kept", "format": "iso" },
+            { "name": "Legacy", "format": "iso" },
+        ])))
+        .unwrap();
+        assert!(stored.assign_ids());
+        assert_eq!(stored.presets[0].id, "a");
+        assert_eq!(stored.presets[1].id.len(), 26);
+        assert!(!stored.assign_ids());
+    }
+}
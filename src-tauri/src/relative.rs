@@ -0,0 +1,153 @@
+//! `{relative}`: the distance between the rendered instant and a reference
+//! instant, in either direction ("in 3 days", "2 weeks ago",
+//! "yesterday at 4:15 PM").
+
+use chrono::{DateTime, Duration, Timelike, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::locale::Locale;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RelativeUnit {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+/// Smallest unit `{relative}` will report. Anything closer than one of these
+/// collapses to "just now" (or "today" for `Day`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Granularity {
+    Second,
+    #[default]
+    Minute,
+    Hour,
+    Day,
+}
+
+/// Describe a duration without calendar context, e.g. "3 hours ago" or
+/// "in 2 weeks". Positive durations are in the future.
+pub fn humanize_duration(d: Duration, granularity: Granularity, locale: &Locale) -> String {
+    let words = locale.relative;
+    let secs = d.num_seconds();
+    let abs = secs.abs();
+    let (unit, n) = if abs < 60 {
+        (RelativeUnit::Second, abs)
+    } else if abs < 3600 {
+        (RelativeUnit::Minute, abs / 60)
+    } else if abs < 86_400 {
+        (RelativeUnit::Hour, abs / 3600)
+    } else {
+        let days = abs / 86_400;
+        // Years from 335 days, so no span reads as "12 months".
+        match days {
+            0..=6 => (RelativeUnit::Day, days),
+            7..=29 => (RelativeUnit::Week, days / 7),
+            30..=334 => (RelativeUnit::Month, days / 30),
+            _ => (RelativeUnit::Year, (days / 365).max(1)),
+        }
+    };
+    let below = match granularity {
+        Granularity::Second => n == 0,
+        Granularity::Minute => unit == RelativeUnit::Second,
+        Granularity::Hour => unit < RelativeUnit::Hour,
+        Granularity::Day => unit < RelativeUnit::Day,
+    };
+    if below {
+        return if granularity == Granularity::Day { words.today } else { words.now }.to_string();
+    }
+    let quantity = (words.unit)(unit, n);
+    let pattern = if secs < 0 { words.past } else { words.future };
+    pattern.replacen("{}", &quantity, 1)
+}
+
+/// Describe `target` relative to `reference`, using calendar days in the
+/// target's timezone so that late last night reads as "yesterday".
+pub fn describe(target: &DateTime<Tz>, reference: DateTime<Utc>, granularity: Granularity, locale: &Locale) -> String {
+    let words = locale.relative;
+    let diff = target.with_timezone(&Utc) - reference;
+    let reference_local = reference.with_timezone(&target.timezone());
+    let day_delta = (target.date_naive() - reference_local.date_naive()).num_days();
+
+    // Within the hour, or later/earlier on the same day: plain durations read
+    // best. At day granularity only the calendar day counts, so twenty
+    // minutes across midnight is still "yesterday".
+    let within_the_hour = granularity != Granularity::Day && diff.num_seconds().abs() < 3600;
+    if within_the_hour || day_delta == 0 {
+        return humanize_duration(diff, granularity, locale);
+    }
+    if day_delta.abs() == 1 {
+        let day = if day_delta < 0 { words.yesterday } else { words.tomorrow };
+        if granularity == Granularity::Day {
+            return day.to_string();
+        }
+        return words
+            .day_at
+            .replace("{day}", day)
+            .replace("{time}", &locale.clock(target.hour(), target.minute()));
+    }
+    // Count whole calendar days so "3 days ago" doesn't depend on the hour.
+    humanize_duration(Duration::days(day_delta), granularity, locale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use chrono_tz::Europe::Berlin;
+
+    fn at(hour: u32, minute: u32, day: u32) -> DateTime<Tz> {
+        Berlin.with_ymd_and_hms(2023, 11, day, hour, minute, 0).unwrap()
+    }
+
+    fn describe_at(target: DateTime<Tz>, reference: DateTime<Tz>, granularity: Granularity, tag: &str) -> String {
+        describe(&target, reference.with_timezone(&Utc), granularity, Locale::resolve(tag))
+    }
+
+    #[test]
+    fn yesterday_and_tomorrow_across_midnight() {
+        let (late, early) = (at(23, 50, 13), at(0, 10, 14));
+        assert_eq!(describe_at(late, early, Granularity::Day, "en-US"), "yesterday");
+        assert_eq!(describe_at(early, late, Granularity::Day, "en-US"), "tomorrow");
+        // Finer granularities keep the plain duration within the hour.
+        assert_eq!(describe_at(late, early, Granularity::Minute, "en-US"), "20 minutes ago");
+        assert_eq!(describe_at(at(21, 15, 13), early, Granularity::Minute, "en-US"), "yesterday at 9:15 PM");
+        assert_eq!(describe_at(at(0, 5, 14), early, Granularity::Day, "en-US"), "today");
+    }
+
+    #[test]
+    fn future_phrasing() {
+        let now = at(12, 0, 14);
+        assert_eq!(describe_at(at(12, 0, 17), now, Granularity::Minute, "en-US"), "in 3 days");
+        assert_eq!(describe_at(at(15, 0, 14), now, Granularity::Minute, "en-US"), "in 3 hours");
+        assert_eq!(describe_at(at(12, 0, 28), now, Granularity::Day, "en-US"), "in 2 weeks");
+        assert_eq!(describe_at(at(12, 0, 30), now, Granularity::Second, "en-US"), "in 2 weeks");
+    }
+
+    #[test]
+    fn other_locales() {
+        let now = at(12, 0, 14);
+        assert_eq!(describe_at(at(9, 30, 13), now, Granularity::Minute, "de-DE"), "gestern um 09:30");
+        assert_eq!(describe_at(at(12, 0, 11), now, Granularity::Minute, "de-DE"), "vor 3 Tagen");
+        assert_eq!(describe_at(at(12, 0, 17), now, Granularity::Minute, "fr-FR"), "dans 3 jours");
+        assert_eq!(describe_at(at(23, 50, 13), at(0, 10, 14), Granularity::Day, "fr-FR"), "hier");
+    }
+
+    #[test]
+    fn months_give_way_to_years() {
+        let en = Locale::resolve("en-US");
+        let ago = |days: i64| humanize_duration(Duration::days(-days), Granularity::Minute, en);
+        assert_eq!(ago(30), "1 month ago");
+        assert_eq!(ago(334), "11 months ago");
+        for days in [335, 360, 364, 365, 729] {
+            assert_eq!(ago(days), "1 year ago", "{} days", days);
+        }
+        assert_eq!(ago(730), "2 years ago");
+    }
+}
@@ -0,0 +1,215 @@
+//! `{season}` and `{season-day}` for either hemisphere, by meteorological
+//! months or by astronomical equinoxes and solstices.
+//!
+//! Equinox and solstice instants use the approximation in Meeus,
+//! *Astronomical Algorithms* ch. 27 (valid 1000–3000 AD, within a minute or
+//! so), so no network or ephemeris data is needed. Outside those years the
+//! astronomical mode falls back to meteorological seasons.
+
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::locale::Locale;
+
+/// The `seasonMode` preference.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SeasonMode {
+    /// Whole months: spring is March–May in the north.
+    #[default]
+    Meteorological,
+    /// Seasons turn at the equinoxes and solstices.
+    Astronomical,
+}
+
+/// The `hemisphere` preference.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Hemisphere {
+    #[default]
+    Northern,
+    Southern,
+}
+
+/// In the order of `Locale::seasons`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    pub fn name(self, locale: &Locale) -> &'static str {
+        locale.seasons[self as usize]
+    }
+
+    fn opposite(self) -> Season {
+        match self {
+            Season::Spring => Season::Autumn,
+            Season::Summer => Season::Winter,
+            Season::Autumn => Season::Spring,
+            Season::Winter => Season::Summer,
+        }
+    }
+}
+
+/// Northern seasons in the order they begin each year, starting from March.
+const NORTHERN: [Season; 4] = [Season::Spring, Season::Summer, Season::Autumn, Season::Winter];
+
+/// The season at `dt` and the local date it began on.
+pub fn season_at(dt: &DateTime<Tz>, mode: SeasonMode, hemisphere: Hemisphere) -> (Season, NaiveDate) {
+    let (northern, start) = match mode {
+        SeasonMode::Meteorological => meteorological(dt.date_naive()),
+        SeasonMode::Astronomical => astronomical(dt).unwrap_or_else(|| meteorological(dt.date_naive())),
+    };
+    let season = match hemisphere {
+        Hemisphere::Northern => northern,
+        Hemisphere::Southern => northern.opposite(),
+    };
+    (season, start)
+}
+
+/// "day 23 of Autumn": 1-based calendar day within the season.
+pub fn season_day(dt: &DateTime<Tz>, mode: SeasonMode, hemisphere: Hemisphere, locale: &Locale) -> String {
+    let (season, start) = season_at(dt, mode, hemisphere);
+    let day = (dt.date_naive() - start).num_days() + 1;
+    locale.season_day.replace("{n}", &day.to_string()).replace("{season}", season.name(locale))
+}
+
+fn meteorological(date: NaiveDate) -> (Season, NaiveDate) {
+    // Shift so March is 0; December..February become 9..11.
+    let shifted = (date.month0() + 10) % 12;
+    let season = NORTHERN[(shifted / 3) as usize];
+    let start_month = (shifted / 3) * 3 + 3; // 3, 6, 9 or 12
+    let start_year = if date.month() < 3 { date.year() - 1 } else { date.year() };
+    let start = NaiveDate::from_ymd_opt(start_year, start_month, 1).unwrap_or(date);
+    (season, start)
+}
+
+/// `None` for years the approximation doesn't cover.
+fn astronomical(dt: &DateTime<Tz>) -> Option<(Season, NaiveDate)> {
+    let year = dt.year();
+    let at = dt.with_timezone(&Utc);
+    // Last year's December solstice covers early January.
+    let mut season = Season::Winter;
+    let mut start = solstice_or_equinox(year - 1, 3)?;
+    for (i, s) in NORTHERN.iter().enumerate() {
+        let begins = solstice_or_equinox(year, i)?;
+        if begins > at {
+            break;
+        }
+        season = *s;
+        start = begins;
+    }
+    Some((season, start.with_timezone(&dt.timezone()).date_naive()))
+}
+
+/// Periodic terms (A, B, C) from Meeus table 27.C.
+const TERMS: [(f64, f64, f64); 24] = [
+    (485.0, 324.96, 1934.136),
+    (203.0, 337.23, 32964.467),
+    (199.0, 342.08, 20.186),
+    (182.0, 27.85, 445267.112),
+    (156.0, 73.14, 45036.886),
+    (136.0, 171.52, 22518.443),
+    (77.0, 222.54, 65928.934),
+    (74.0, 296.72, 3034.906),
+    (70.0, 243.58, 9037.513),
+    (58.0, 119.81, 33718.147),
+    (52.0, 297.17, 150.678),
+    (50.0, 21.02, 2281.226),
+    (45.0, 247.54, 29929.562),
+    (44.0, 325.15, 31555.956),
+    (29.0, 60.93, 4443.417),
+    (18.0, 155.12, 67555.328),
+    (17.0, 288.79, 4562.452),
+    (16.0, 198.04, 62894.029),
+    (14.0, 199.76, 31436.921),
+    (12.0, 95.39, 14577.848),
+    (12.0, 287.11, 31931.756),
+    (12.0, 320.81, 34777.259),
+    (9.0, 227.73, 1222.114),
+    (8.0, 15.45, 16859.074),
+];
+
+/// Mean instants (JDE) as polynomials in millennia from 2000, Meeus table 27.B;
+/// index 0 is the March equinox, then June, September and December.
+const MEAN: [[f64; 5]; 4] = [
+    [2451623.80984, 365242.37404, 0.05169, -0.00411, -0.00057],
+    [2451716.56767, 365241.62603, 0.00325, 0.00888, -0.00030],
+    [2451810.21715, 365242.01767, -0.11575, 0.00337, 0.00078],
+    [2451900.05952, 365242.74049, -0.06223, -0.00823, 0.00032],
+];
+
+/// Terrestrial Time runs about 69 s ahead of UTC in this era.
+const DELTA_T_SECONDS: f64 = 69.0;
+
+/// The `which`-th (0 = March equinox .. 3 = December solstice) event of
+/// `year`, or `None` outside 1000–3000, where the approximation drifts.
+fn solstice_or_equinox(year: i32, which: usize) -> Option<DateTime<Utc>> {
+    if !(1000..=3000).contains(&year) {
+        return None;
+    }
+    let y = (year as f64 - 2000.0) / 1000.0;
+    let c = MEAN[which];
+    let jde0 = c[0] + y * (c[1] + y * (c[2] + y * (c[3] + y * c[4])));
+    let t = (jde0 - 2451545.0) / 36525.0;
+    let w = (35999.373 * t - 2.47).to_radians();
+    let delta_lambda = 1.0 + 0.0334 * w.cos() + 0.0007 * (2.0 * w).cos();
+    let s: f64 = TERMS.iter().map(|(a, b, c)| a * (b + c * t).to_radians().cos()).sum();
+    let jde = jde0 + 0.00001 * s / delta_lambda;
+    let unix = (jde - 2440587.5) * 86400.0 - DELTA_T_SECONDS;
+    DateTime::from_timestamp(unix.round() as i64, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(tz: Tz, y: i32, m: u32, d: u32) -> DateTime<Tz> {
+        tz.with_ymd_and_hms(y, m, d, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn equinoxes_and_solstices_of_2024() {
+        let published = [(3, 20, 3, 6), (6, 20, 20, 51), (9, 22, 12, 44), (12, 21, 9, 20)];
+        for (which, (m, d, h, min)) in published.into_iter().enumerate() {
+            let expected = Utc.with_ymd_and_hms(2024, m, d, h, min, 0).unwrap();
+            let error = (solstice_or_equinox(2024, which).unwrap() - expected).num_seconds().abs();
+            assert!(error < 120, "event {} is {} s off", which, error);
+        }
+    }
+
+    #[test]
+    fn hemispheres_and_names() {
+        let en = Locale::resolve("en-US");
+        let autumn = at(Tz::Europe__London, 2024, 10, 14);
+        assert_eq!(season_at(&autumn, SeasonMode::Meteorological, Hemisphere::Northern).0.name(en), "Autumn");
+        let sydney = at(Tz::Australia__Sydney, 2024, 10, 14);
+        assert_eq!(season_at(&sydney, SeasonMode::Meteorological, Hemisphere::Southern).0, Season::Spring);
+        assert_eq!(season_day(&autumn, SeasonMode::Meteorological, Hemisphere::Northern, en), "day 44 of Autumn");
+        let de = Locale::resolve("de-DE");
+        assert_eq!(season_day(&autumn, SeasonMode::Meteorological, Hemisphere::Northern, de), "Herbst, Tag 44");
+    }
+
+    #[test]
+    fn astronomical_turns_at_the_equinox() {
+        let utc = |d: u32| Tz::UTC.with_ymd_and_hms(2024, 3, d, 12, 0, 0).unwrap();
+        let season = |d| season_at(&utc(d), SeasonMode::Astronomical, Hemisphere::Northern);
+        assert_eq!(season(19), (Season::Winter, NaiveDate::from_ymd_opt(2023, 12, 22).unwrap()));
+        assert_eq!(season(20), (Season::Spring, NaiveDate::from_ymd_opt(2024, 3, 20).unwrap()));
+    }
+
+    #[test]
+    fn astronomical_falls_back_outside_the_approximation() {
+        for dt in [at(Tz::UTC, 800, 3, 25), at(Tz::UTC, 3500, 1, 5)] {
+            let astronomical = season_at(&dt, SeasonMode::Astronomical, Hemisphere::Northern);
+            assert_eq!(astronomical, season_at(&dt, SeasonMode::Meteorological, Hemisphere::Northern));
+        }
+        assert_eq!(solstice_or_equinox(999, 0), None);
+    }
+}
@@ -0,0 +1,783 @@
+//! Lexer and parser for `{token}` format strings.
+//!
+//! Grammar: literal text, `{name}` tokens with optional `:modifier` suffixes
+//! and `@Zone` / `@calendar` overrides (`{year@hebrew@Asia/Jerusalem}`),
+//! `{@Zone}` ... `{/}` scopes taking the same overrides, and `{{` / `}}` for
+//! literal braces. A scope left open runs to the end of the template.
+//! `{strftime:PATTERN}` hands everything after the colon to chrono's strftime
+//! formatter; it takes no modifiers and follows the enclosing scope's zone
+//! but always renders Gregorian dates.
+//! Parsing never fails outright; problems are collected as [`Diagnostic`]s and
+//! the offending text is kept verbatim so old presets keep rendering. Callers
+//! decide whether diagnostics are fatal (strict) or ignored (lenient).
+
+use chrono::format::{Item, StrftimeItems};
+use chrono_tz::Tz;
+use serde::Serialize;
+
+use crate::calendars::{Calendar, CALENDARS};
+use crate::epochs::Epoch;
+use crate::error::{suggestions, TimestampError};
+use crate::ids::IdKind;
+use crate::zones;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Token {
+    DayAbb,
+    MonthAbb,
+    Day,
+    Month,
+    Date,
+    Time,
+    TimeLocale,
+    Period,
+    Year,
+    Era,
+    Time24,
+    Seconds,
+    Milliseconds,
+    Hours,
+    Hours24,
+    Minutes,
+    DayOrdinal,
+    MonthNum,
+    MonthNumPad,
+    YearShort,
+    Week,
+    IsoWeek,
+    IsoWeekYear,
+    WeekOfMonth,
+    DayOfYear,
+    DaysLeftInYear,
+    IsoOrdinal,
+    JulianDay,
+    ModifiedJulianDay,
+    Quarter,
+    Season,
+    SeasonDay,
+    FiscalYear,
+    FiscalQuarter,
+    FiscalPeriod,
+    FiscalWeek,
+    Timezone,
+    UtcOffset,
+    TimezoneFull,
+    TimezoneLong,
+    Relative,
+    Epoch(Epoch),
+    Id(IdKind),
+}
+
+/// Every token with its documentation; the single source for parsing,
+/// suggestions and the `list_tokens` reference.
+pub struct TokenInfo {
+    pub name: &'static str,
+    pub token: Token,
+    pub category: &'static str,
+    pub description: &'static str,
+}
+
+const fn info(name: &'static str, token: Token, category: &'static str, description: &'static str) -> TokenInfo {
+    TokenInfo { name, token, category, description }
+}
+
+pub const TOKENS: &[TokenInfo] = &[
+    info("day", Token::Day, "Date", "Full day name"),
+    info("day-abb", Token::DayAbb, "Date", "Abbreviated day name"),
+    info("month", Token::Month, "Date", "Full month name"),
+    info("month-abb", Token::MonthAbb, "Date", "Abbreviated month name"),
+    info("date", Token::Date, "Date", "Day of the month"),
+    info("day-ordinal", Token::DayOrdinal, "Date", "Day of the month with ordinal suffix"),
+    info("year", Token::Year, "Date", "Full year"),
+    info("year-short", Token::YearShort, "Date", "Two-digit year"),
+    info("era", Token::Era, "Date", "Era of the year, e.g. AD, or Reiwa with @japanese"),
+    info("month-num", Token::MonthNum, "Date", "Month number"),
+    info("month-num-pad", Token::MonthNumPad, "Date", "Month number, zero-padded"),
+    info("time", Token::Time, "Time", "12-hour time without period"),
+    info("time-locale", Token::TimeLocale, "Time", "Time in the locale's clock style"),
+    info("time24", Token::Time24, "Time", "24-hour time"),
+    info("hours", Token::Hours, "Time", "Hour, 12-hour clock"),
+    info("hours24", Token::Hours24, "Time", "Hour, 24-hour clock"),
+    info("minutes", Token::Minutes, "Time", "Minutes, zero-padded"),
+    info("seconds", Token::Seconds, "Time", "Seconds, zero-padded"),
+    info("milliseconds", Token::Milliseconds, "Time", "Milliseconds, zero-padded"),
+    info("period", Token::Period, "Time", "AM/PM marker"),
+    info("week", Token::Week, "Calendar", "Week of the year, numbered per the weekStart preference"),
+    info("iso-week", Token::IsoWeek, "Calendar", "ISO 8601 week number"),
+    info("iso-week-year", Token::IsoWeekYear, "Calendar", "Year the ISO week belongs to"),
+    info("week-of-month", Token::WeekOfMonth, "Calendar", "Week of the month"),
+    info("day-of-year", Token::DayOfYear, "Calendar", "Day of the year, 1-366"),
+    info("days-left-in-year", Token::DaysLeftInYear, "Calendar", "Days remaining after today in the year"),
+    info("iso-ordinal", Token::IsoOrdinal, "Calendar", "ISO 8601 ordinal date, e.g. 2026-290"),
+    info("julian-day", Token::JulianDay, "Calendar", "Julian Day Number of the date"),
+    info("modified-julian-day", Token::ModifiedJulianDay, "Calendar", "Modified Julian Day of the date"),
+    info("quarter", Token::Quarter, "Calendar", "Quarter of the year"),
+    info("season", Token::Season, "Calendar", "Season, per the hemisphere and seasonMode preferences"),
+    info("season-day", Token::SeasonDay, "Calendar", "Day within the season, e.g. \"day 23 of Autumn\""),
+    info("fiscal-year", Token::FiscalYear, "Fiscal", "Fiscal year, named for the year it ends in"),
+    info("fiscal-quarter", Token::FiscalQuarter, "Fiscal", "Quarter of the fiscal year"),
+    info("fiscal-period", Token::FiscalPeriod, "Fiscal", "Fiscal month, or retail period under a 4-4-5 calendar"),
+    info("fiscal-week", Token::FiscalWeek, "Fiscal", "Week of the fiscal year"),
+    info("timezone", Token::Timezone, "Timezone", "Timezone abbreviation"),
+    info("utc-offset", Token::UtcOffset, "Timezone", "Offset from UTC"),
+    info("timezone-full", Token::TimezoneFull, "Timezone", "IANA timezone name"),
+    info("timezone-long", Token::TimezoneLong, "Timezone", "Long timezone name"),
+    info("relative", Token::Relative, "Relative", "Distance from now, e.g. \"in 3 days\""),
+    info("unix", Token::Epoch(Epoch::UnixSeconds), "Epoch", "Unix seconds"),
+    info("unix-ms", Token::Epoch(Epoch::UnixMillis), "Epoch", "Unix milliseconds"),
+    info("unix-us", Token::Epoch(Epoch::UnixMicros), "Epoch", "Unix microseconds"),
+    info("unix-ns", Token::Epoch(Epoch::UnixNanos), "Epoch", "Unix nanoseconds"),
+    info("unix-hex", Token::Epoch(Epoch::UnixHex), "Epoch", "Unix seconds in hex"),
+    info("filetime", Token::Epoch(Epoch::Filetime), "Epoch", "Windows FILETIME, 16 hex digits"),
+    info("dotnet-ticks", Token::Epoch(Epoch::DotnetTicks), "Epoch", ".NET DateTime ticks"),
+    info("cocoa", Token::Epoch(Epoch::Cocoa), "Epoch", "Apple Cocoa / Core Data seconds since 2001"),
+    info("ldap", Token::Epoch(Epoch::Ldap), "Epoch", "Active Directory / LDAP timestamp"),
+    info("ntp", Token::Epoch(Epoch::Ntp), "Epoch", "NTP seconds since 1900"),
+    info("gps", Token::Epoch(Epoch::Gps), "Epoch", "GPS seconds since 1980-01-06"),
+    info("excel", Token::Epoch(Epoch::Excel), "Epoch", "Excel / Lotus serial date in local time"),
+    info("ulid", Token::Id(IdKind::Ulid), "ID", "New ULID for the instant"),
+    info("uuidv7", Token::Id(IdKind::Uuidv7), "ID", "New UUIDv7 for the instant"),
+    info("ksuid", Token::Id(IdKind::Ksuid), "ID", "New KSUID for the instant"),
+    info("snowflake", Token::Id(IdKind::Snowflake), "ID", "New Snowflake ID, per the snowflake preference"),
+];
+
+impl Token {
+    pub fn from_name(name: &str) -> Option<Token> {
+        TOKENS.iter().find(|t| t.name == name).map(|t| t.token)
+    }
+}
+
+/// Post-processing applied to a token's value: `{name:modifier:modifier}`,
+/// applied left to right.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Modifier {
+    Upper,
+    Lower,
+    /// Capitalize the first letter of each word.
+    Title,
+    /// Left-pad with zeros to the given width.
+    Pad(usize),
+    /// Left-pad with spaces to the given width.
+    PadSpace(usize),
+    /// Strip leading zeros.
+    Unpad,
+    /// Keep the first N characters.
+    Abb(usize),
+}
+
+impl std::fmt::Display for Modifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Modifier::Upper => f.write_str("upper"),
+            Modifier::Lower => f.write_str("lower"),
+            Modifier::Title => f.write_str("title"),
+            Modifier::Pad(n) => write!(f, "pad{}", n),
+            Modifier::PadSpace(n) => write!(f, "pad-space{}", n),
+            Modifier::Unpad => f.write_str("unpad"),
+            Modifier::Abb(n) => write!(f, "abb{}", n),
+        }
+    }
+}
+
+/// Concrete spellings offered as suggestions for a misspelled modifier.
+const MODIFIER_EXAMPLES: &[&str] = &["upper", "lower", "title", "pad2", "pad3", "pad-space", "unpad", "abb1", "abb2", "abb3"];
+
+/// Modifier syntax with documentation, as listed by `list_tokens`.
+pub const MODIFIERS: &[(&str, &str)] = &[
+    ("upper", "Uppercase"),
+    ("lower", "Lowercase"),
+    ("title", "Capitalize each word"),
+    ("pad<n>", "Pad with zeros to n characters, e.g. pad2"),
+    ("pad-space<n>", "Pad with spaces to n characters; pad-space is pad-space2"),
+    ("unpad", "Remove leading zeros"),
+    ("abb<n>", "First n characters, e.g. abb3"),
+];
+
+impl Modifier {
+    const MAX_WIDTH: usize = 32;
+
+    pub fn from_name(name: &str) -> Option<Modifier> {
+        let width = |digits: &str| digits.parse().ok().filter(|n| (1..=Self::MAX_WIDTH).contains(n));
+        Some(match name {
+            "upper" => Modifier::Upper,
+            "lower" => Modifier::Lower,
+            "title" => Modifier::Title,
+            "unpad" => Modifier::Unpad,
+            "pad-space" => Modifier::PadSpace(2),
+            _ => {
+                if let Some(n) = name.strip_prefix("pad-space") {
+                    Modifier::PadSpace(width(n)?)
+                } else if let Some(n) = name.strip_prefix("pad") {
+                    Modifier::Pad(width(n)?)
+                } else if let Some(n) = name.strip_prefix("abb") {
+                    Modifier::Abb(width(n)?)
+                } else {
+                    return None;
+                }
+            }
+        })
+    }
+
+    /// Whether the modifier only changes letter case, so a parser matching
+    /// case-insensitively can still read the value back.
+    pub fn is_case(self) -> bool {
+        matches!(self, Modifier::Upper | Modifier::Lower | Modifier::Title)
+    }
+
+    pub fn apply(self, value: String) -> String {
+        let pad = |value: String, width: usize, fill: char| {
+            let len = value.chars().count();
+            if len >= width {
+                return value;
+            }
+            let fill: String = std::iter::repeat_n(fill, width - len).collect();
+            match value.strip_prefix('-') {
+                Some(digits) if fill.starts_with('0') => format!("-{}{}", fill, digits),
+                _ => fill + &value,
+            }
+        };
+        match self {
+            Modifier::Upper => value.to_uppercase(),
+            Modifier::Lower => value.to_lowercase(),
+            Modifier::Title => {
+                let mut out = String::with_capacity(value.len());
+                let mut word_start = true;
+                for c in value.chars() {
+                    if word_start {
+                        out.extend(c.to_uppercase());
+                    } else {
+                        out.push(c);
+                    }
+                    word_start = c.is_whitespace() || c == '-';
+                }
+                out
+            }
+            Modifier::Pad(width) => pad(value, width, '0'),
+            Modifier::PadSpace(width) => pad(value, width, ' '),
+            Modifier::Unpad => {
+                let trimmed = value.trim_start_matches('0');
+                if trimmed.starts_with(|c: char| c.is_ascii_digit()) {
+                    trimmed.to_string()
+                } else if value.starts_with('0') && trimmed.is_empty() {
+                    "0".to_string()
+                } else {
+                    value
+                }
+            }
+            Modifier::Abb(n) => value.chars().take(n).collect(),
+        }
+    }
+}
+
+/// Byte range into the source string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Lexeme {
+    /// Run of plain text.
+    Text(Span),
+    /// `{{` or `}}`.
+    Escape(char, Span),
+    /// `{...}`; `body` excludes the braces.
+    Token { span: Span, body: Span },
+    /// `{` with no closing brace before the next `{` or end of input.
+    Unclosed(Span),
+    /// Lone `}`.
+    StrayClose(Span),
+}
+
+fn lex(src: &str) -> Vec<Lexeme> {
+    let bytes = src.as_bytes();
+    let mut out = Vec::new();
+    let mut text_start = 0;
+    let mut i = 0;
+
+    let flush = |out: &mut Vec<Lexeme>, start: usize, end: usize| {
+        if end > start {
+            out.push(Lexeme::Text(Span { start, end }));
+        }
+    };
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'{' if bytes.get(i + 1) == Some(&b'{') => {
+                flush(&mut out, text_start, i);
+                out.push(Lexeme::Escape('{', Span { start: i, end: i + 2 }));
+                i += 2;
+                text_start = i;
+            }
+            b'}' if bytes.get(i + 1) == Some(&b'}') => {
+                flush(&mut out, text_start, i);
+                out.push(Lexeme::Escape('}', Span { start: i, end: i + 2 }));
+                i += 2;
+                text_start = i;
+            }
+            b'{' => {
+                flush(&mut out, text_start, i);
+                let rest = &bytes[i + 1..];
+                let close = rest.iter().position(|b| *b == b'}');
+                let reopen = rest.iter().position(|b| *b == b'{');
+                match close {
+                    Some(c) if reopen.is_none_or(|o| c < o) => {
+                        let end = i + 1 + c + 1;
+                        out.push(Lexeme::Token {
+                            span: Span { start: i, end },
+                            body: Span { start: i + 1, end: end - 1 },
+                        });
+                        i = end;
+                    }
+                    _ => {
+                        let end = reopen.map_or(bytes.len(), |o| i + 1 + o);
+                        out.push(Lexeme::Unclosed(Span { start: i, end }));
+                        i = end;
+                    }
+                }
+                text_start = i;
+            }
+            b'}' => {
+                flush(&mut out, text_start, i);
+                out.push(Lexeme::StrayClose(Span { start: i, end: i + 1 }));
+                i += 1;
+                text_start = i;
+            }
+            _ => i += 1,
+        }
+    }
+    flush(&mut out, text_start, bytes.len());
+    out
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Node {
+    Literal(String),
+    Token(Placeholder),
+    /// `{strftime:...}`, validated and parsed once.
+    Strftime { pattern: String, items: Vec<Item<'static>>, zone: Option<Tz> },
+}
+
+/// Canonical source text, e.g. `{hours24:pad2@Asia/Tokyo}`.
+impl std::fmt::Display for Placeholder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = TOKENS.iter().find(|t| t.token == self.token).map_or("?", |t| t.name);
+        write!(f, "{{{}", name)?;
+        for m in &self.modifiers {
+            write!(f, ":{}", m)?;
+        }
+        if let Some(calendar) = self.calendar {
+            write!(f, "@{}", calendar.name())?;
+        }
+        if let Some(zone) = self.zone {
+            write!(f, "@{}", zone.name())?;
+        }
+        f.write_str("}")
+    }
+}
+
+/// What a render callback is asked to produce.
+pub enum Field<'a> {
+    /// `in_date` is set for a `{month}` that directly follows `{date}` or
+    /// `{day-ordinal}`, with only literals between, since some languages
+    /// inflect the name there.
+    Token { token: Token, calendar: Option<Calendar>, in_date: bool },
+    Strftime(&'a [Item<'static>]),
+}
+
+pub const STRFTIME_PREFIX: &str = "strftime:";
+
+/// A `{name:modifiers@Zone}` occurrence in a template.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Placeholder {
+    pub token: Token,
+    pub modifiers: Vec<Modifier>,
+    /// Zone from `@Zone` or the enclosing scope; `None` renders in the call's zone.
+    pub zone: Option<Tz>,
+    /// Calendar from `@calendar` or the enclosing scope; `None` is Gregorian.
+    pub calendar: Option<Calendar>,
+}
+
+/// Overrides from the `@...` suffixes of a token or scope.
+#[derive(Clone, Copy, Debug, Default)]
+struct Scope {
+    zone: Option<Tz>,
+    calendar: Option<Calendar>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiagnosticKind {
+    UnknownToken,
+    EmptyToken,
+    InvalidModifier,
+    UnknownTimezone,
+    UnknownCalendar,
+    UnmatchedScope,
+    InvalidStrftime,
+    UnclosedBrace,
+    UnmatchedBrace,
+}
+
+/// A problem found while parsing. `start`/`end` are UTF-16 offsets so the
+/// webview can use them directly as string indices.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    pub message: String,
+    pub start: usize,
+    pub end: usize,
+    /// Closest known names, for unknown tokens and modifiers.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub suggestions: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Template {
+    pub nodes: Vec<Node>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Template {
+    pub fn parse(src: &str) -> Template {
+        let mut template = Template::default();
+        // Open `{@Zone}` scopes; a scope whose overrides didn't resolve is
+        // kept empty so its `{/}` still pairs up.
+        let mut scopes: Vec<Scope> = Vec::new();
+        for lexeme in lex(src) {
+            match lexeme {
+                Lexeme::Text(span) => template.push_literal(&src[span.start..span.end]),
+                Lexeme::Escape(c, _) => template.push_literal(c.encode_utf8(&mut [0; 4])),
+                Lexeme::Token { span, body } if &src[body.start..body.end] == "/" => {
+                    if scopes.pop().is_none() {
+                        template.diagnose(src, span, DiagnosticKind::UnmatchedScope, "'{/}' without an open '{@Zone}' scope".into());
+                        template.push_literal(&src[span.start..span.end]);
+                    }
+                }
+                Lexeme::Token { span, body } if src[body.start..body.end].starts_with('@') => {
+                    let scope = template.overrides(src, Span { start: body.start + 1, end: body.end });
+                    if scope.is_none() {
+                        template.push_literal(&src[span.start..span.end]);
+                    }
+                    scopes.push(scope.unwrap_or_default());
+                }
+                Lexeme::Token { span, body } => {
+                    let scope = Scope {
+                        zone: scopes.iter().rev().find_map(|s| s.zone),
+                        calendar: scopes.iter().rev().find_map(|s| s.calendar),
+                    };
+                    if let Some(pattern) = src[body.start..body.end].strip_prefix(STRFTIME_PREFIX) {
+                        let pattern_span = Span { start: body.start + STRFTIME_PREFIX.len(), end: body.end };
+                        match template.strftime(src, pattern, pattern_span) {
+                            Some(items) => template.nodes.push(Node::Strftime { pattern: pattern.to_string(), items, zone: scope.zone }),
+                            None => template.push_literal(&src[span.start..span.end]),
+                        }
+                        continue;
+                    }
+                    match template.placeholder(src, span, body, scope) {
+                        Some(placeholder) => template.nodes.push(Node::Token(placeholder)),
+                        None => template.push_literal(&src[span.start..span.end]),
+                    }
+                }
+                Lexeme::Unclosed(span) => {
+                    template.diagnose(src, span, DiagnosticKind::UnclosedBrace, "Missing closing '}' (use '{{' for a literal brace)".into());
+                    template.push_literal(&src[span.start..span.end]);
+                }
+                Lexeme::StrayClose(span) => {
+                    template.diagnose(src, span, DiagnosticKind::UnmatchedBrace, "Unmatched '}' (use '}}' for a literal brace)".into());
+                    template.push_literal("}");
+                }
+            }
+        }
+        template
+    }
+
+    /// Single render pass: each node is written once, so substituted values
+    /// are never re-scanned for tokens.
+    pub fn render_with(&self, mut value: impl FnMut(Field<'_>, Option<Tz>) -> String) -> String {
+        let mut out = String::new();
+        let mut after_day = false;
+        for node in &self.nodes {
+            match node {
+                Node::Literal(s) => out.push_str(s),
+                Node::Token(p) => {
+                    let field = Field::Token { token: p.token, calendar: p.calendar, in_date: after_day && p.token == Token::Month };
+                    let v = p.modifiers.iter().fold(value(field, p.zone), |v, m| m.apply(v));
+                    out.push_str(&v);
+                    after_day = matches!(p.token, Token::Date | Token::DayOrdinal);
+                }
+                Node::Strftime { items, zone, .. } => {
+                    out.push_str(&value(Field::Strftime(items), *zone));
+                    after_day = false;
+                }
+            }
+        }
+        out
+    }
+
+    /// Resolve a `{...}` body, recording a diagnostic for each problem.
+    fn placeholder(&mut self, src: &str, span: Span, body: Span, scope: Scope) -> Option<Placeholder> {
+        let problems = self.diagnostics.len();
+        let text = &src[body.start..body.end];
+        let (spec, overrides) = match text.split_once('@') {
+            Some((spec, _)) => {
+                let start = body.start + spec.len() + 1;
+                let own = self.overrides(src, Span { start, end: body.end }).unwrap_or_default();
+                (spec, Scope { zone: own.zone.or(scope.zone), calendar: own.calendar.or(scope.calendar) })
+            }
+            None => (text, scope),
+        };
+        let mut parts = spec.split(':');
+        let name = parts.next().unwrap_or_default();
+        let token = Token::from_name(name);
+        if token.is_none() {
+            if name.trim().is_empty() {
+                self.diagnose(src, span, DiagnosticKind::EmptyToken, "Empty token".into());
+            } else {
+                self.diagnose(src, span, DiagnosticKind::UnknownToken, format!("Unknown token '{{{}}}'", name));
+                self.suggest(name, TOKENS.iter().map(|t| t.name).chain(["strftime"]));
+            }
+        }
+
+        let mut modifiers = Vec::new();
+        let mut offset = body.start + name.len();
+        for part in parts {
+            let part_span = Span { start: offset, end: offset + 1 + part.len() };
+            offset = part_span.end;
+            match Modifier::from_name(part) {
+                Some(m) => modifiers.push(m),
+                None if part.is_empty() => {
+                    self.diagnose(src, part_span, DiagnosticKind::InvalidModifier, "Empty modifier".into());
+                }
+                None => {
+                    self.diagnose(src, part_span, DiagnosticKind::InvalidModifier, format!("Unknown modifier ':{}'", part));
+                    self.suggest(part, MODIFIER_EXAMPLES.iter().copied());
+                }
+            }
+        }
+        let placeholder = Placeholder { token: token?, modifiers, zone: overrides.zone, calendar: overrides.calendar };
+        (self.diagnostics.len() == problems).then_some(placeholder)
+    }
+
+    /// Parse a strftime pattern, pointing at the first specifier chrono rejects.
+    fn strftime(&mut self, src: &str, pattern: &str, span: Span) -> Option<Vec<Item<'static>>> {
+        if pattern.is_empty() {
+            self.diagnose(src, span, DiagnosticKind::InvalidStrftime, "Empty strftime pattern".into());
+            return None;
+        }
+        if let Ok(items) = StrftimeItems::new(pattern).parse_to_owned() {
+            return Some(items);
+        }
+        let (start, end) = bad_specifier(pattern).unwrap_or((0, pattern.len()));
+        let bad = &pattern[start..end];
+        self.diagnose(
+            src,
+            Span { start: span.start + start, end: span.start + end },
+            DiagnosticKind::InvalidStrftime,
+            format!("Invalid strftime specifier '{}'", bad),
+        );
+        None
+    }
+
+    /// Resolve `@`-separated zone and calendar names once at parse time.
+    fn overrides(&mut self, src: &str, span: Span) -> Option<Scope> {
+        let mut scope = Scope::default();
+        let mut ok = true;
+        let mut start = span.start;
+        for name in src[span.start..span.end].split('@') {
+            let part = Span { start, end: start + name.len() };
+            start = part.end + 1;
+            if let Some(calendar) = Calendar::from_name(name) {
+                scope.calendar = Some(calendar);
+            } else if let Some(zone) = self.zone(src, part) {
+                scope.zone = Some(zone);
+            } else {
+                ok = false;
+            }
+        }
+        ok.then_some(scope)
+    }
+
+    fn zone(&mut self, src: &str, span: Span) -> Option<Tz> {
+        let name = &src[span.start..span.end];
+        if name.trim().is_empty() {
+            self.diagnose(src, span, DiagnosticKind::UnknownTimezone, "Empty timezone after '@'".into());
+            return None;
+        }
+        match zones::lookup(name) {
+            Ok(tz) => Some(tz),
+            Err(TimestampError::UnknownTimezone { suggestions: zone_suggestions, .. }) => {
+                // A near miss on a calendar name ("Hebrew", "hebrw") beats a zone guess.
+                let calendars = if name.contains('/') { Vec::new() } else { suggestions(name, CALENDARS.iter().map(|(n, _)| *n), 3) };
+                if calendars.is_empty() {
+                    self.diagnose(src, span, DiagnosticKind::UnknownTimezone, format!("Unknown timezone '{}'", name.trim()));
+                } else {
+                    self.diagnose(src, span, DiagnosticKind::UnknownCalendar, format!("Unknown calendar '{}'", name.trim()));
+                }
+                if let Some(d) = self.diagnostics.last_mut() {
+                    d.suggestions = if calendars.is_empty() { zone_suggestions } else { calendars };
+                }
+                None
+            }
+            Err(_) => None,
+        }
+    }
+
+    fn suggest<'a>(&mut self, name: &str, candidates: impl IntoIterator<Item = &'a str>) {
+        if let Some(d) = self.diagnostics.last_mut() {
+            d.suggestions = suggestions(name, candidates, 3);
+        }
+    }
+
+    fn push_literal(&mut self, s: &str) {
+        if let Some(Node::Literal(prev)) = self.nodes.last_mut() {
+            prev.push_str(s);
+        } else {
+            self.nodes.push(Node::Literal(s.to_string()));
+        }
+    }
+
+    fn diagnose(&mut self, src: &str, span: Span, kind: DiagnosticKind, message: String) {
+        self.diagnostics.push(Diagnostic {
+            kind,
+            message,
+            start: utf16_offset(src, span.start),
+            end: utf16_offset(src, span.end),
+            suggestions: Vec::new(),
+        });
+    }
+}
+
+/// Byte range of the first `%` specifier that doesn't parse on its own.
+/// Specifiers are at most five bytes (`%-:3f` style flags and widths).
+fn bad_specifier(pattern: &str) -> Option<(usize, usize)> {
+    let mut i = 0;
+    while let Some(p) = pattern[i..].find('%') {
+        let start = i + p;
+        let valid_end = (start + 2..=start + 5)
+            .find(|end| pattern.get(start..*end).is_some_and(|spec| StrftimeItems::new(spec).parse().is_ok()));
+        match valid_end {
+            Some(end) => i = end,
+            None => {
+                let end = pattern[start + 1..].chars().next().map_or(pattern.len(), |c| start + 1 + c.len_utf8());
+                return Some((start, end));
+            }
+        }
+    }
+    None
+}
+
+fn utf16_offset(src: &str, byte: usize) -> usize {
+    src[..byte].encode_utf16().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(src: &str) -> Vec<DiagnosticKind> {
+        Template::parse(src).diagnostics.iter().map(|d| d.kind).collect()
+    }
+
+    /// Literal nodes and token names, in order.
+    fn shape(src: &str) -> Vec<String> {
+        Template::parse(src)
+            .nodes
+            .iter()
+            .map(|n| match n {
+                Node::Literal(s) => s.clone(),
+                Node::Token(p) => p.to_string(),
+                Node::Strftime { pattern, .. } => format!("strftime:{}", pattern),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn tokens_and_literals() {
+        assert_eq!(shape("{day}, {month} {date}"), ["{day}", ", ", "{month}", " ", "{date}"]);
+        assert_eq!(shape("{year@hebrew}"), ["{year@hebrew}"]);
+        assert!(kinds("{day}, {month} {date}").is_empty());
+    }
+
+    #[test]
+    fn escaped_braces_are_literal() {
+        assert_eq!(shape("{{date}} is {date}"), ["{date} is ", "{date}"]);
+        assert_eq!(shape("}}{{"), ["}{"]);
+        assert!(kinds("{{date}}").is_empty());
+    }
+
+    #[test]
+    fn unclosed_and_stray_braces() {
+        assert_eq!(kinds("{date"), [DiagnosticKind::UnclosedBrace]);
+        // The unclosed brace ends at the next '{', which still opens a token.
+        assert_eq!(kinds("{date {year}"), [DiagnosticKind::UnclosedBrace]);
+        assert_eq!(shape("{date {year}"), ["{date ", "{year}"]);
+        assert_eq!(kinds("date}"), [DiagnosticKind::UnmatchedBrace]);
+        assert_eq!(shape("date}"), ["date}"]);
+    }
+
+    #[test]
+    fn unknown_tokens_are_kept_and_suggested() {
+        let template = Template::parse("{yaer} {}");
+        assert_eq!(template.nodes, [Node::Literal("{yaer} {}".into())]);
+        let d = &template.diagnostics;
+        assert_eq!((d[0].kind, d[1].kind), (DiagnosticKind::UnknownToken, DiagnosticKind::EmptyToken));
+        assert!(d[0].suggestions.contains(&"year".to_string()));
+    }
+
+    fn apply(name: &str, value: &str) -> String {
+        Modifier::from_name(name).unwrap().apply(value.to_string())
+    }
+
+    #[test]
+    fn padding_width_and_fill() {
+        assert_eq!(apply("pad2", "7"), "07");
+        assert_eq!(apply("pad4", "-7"), "-007");
+        assert_eq!(apply("pad2", "123"), "123");
+        assert_eq!(apply("pad-space", "7"), " 7");
+        assert_eq!(apply("pad-space4", "ab"), "  ab");
+        assert_eq!(apply("unpad", "007"), "7");
+        assert_eq!(apply("unpad", "00"), "0");
+        assert_eq!(Modifier::from_name("pad0"), None);
+        assert_eq!(Modifier::from_name("pad33"), None);
+    }
+
+    #[test]
+    fn case_and_abbreviation() {
+        assert_eq!(apply("upper", "März"), "MÄRZ");
+        assert_eq!(apply("lower", "PM"), "pm");
+        assert_eq!(apply("title", "day-of the week"), "Day-Of The Week");
+        assert_eq!(apply("abb2", "Über"), "Üb");
+    }
+
+    #[test]
+    fn modifiers_apply_left_to_right() {
+        let template = Template::parse("{month:abb3:upper} {date:pad3:unpad}");
+        let rendered = template.render_with(|field, _| match field {
+            Field::Token { token: Token::Month, .. } => "september".into(),
+            Field::Token { .. } => "04".into(),
+            Field::Strftime(_) => String::new(),
+        });
+        assert_eq!(rendered, "SEP 4");
+    }
+
+    #[test]
+    fn unknown_modifiers_are_diagnosed() {
+        let template = Template::parse("{date:shout}");
+        let d = &template.diagnostics[0];
+        assert_eq!((d.kind, d.message.as_str()), (DiagnosticKind::InvalidModifier, "Unknown modifier ':shout'"));
+        assert_eq!(kinds("{date:}"), [DiagnosticKind::InvalidModifier]);
+        assert_eq!(template.nodes, [Node::Literal("{date:shout}".into())]);
+    }
+
+    #[test]
+    fn spans_are_utf16_offsets() {
+        // The emoji is 4 bytes but 2 UTF-16 units; "é" is 2 bytes but 1 unit.
+        let d = &Template::parse("😀 é {nope}").diagnostics[0];
+        assert_eq!((d.start, d.end), (5, 11));
+        let d = &Template::parse("日付 {date:padx}").diagnostics[0];
+        assert_eq!((d.kind, d.start, d.end), (DiagnosticKind::InvalidModifier, 8, 13));
+    }
+}
@@ -1,255 +1,707 @@
-use chrono::{Datelike, Local, Timelike, Utc, Duration, TimeZone, Offset};
+use chrono::format::Item;
+use chrono::{DateTime, Datelike, NaiveDate, Timelike, Utc, TimeZone};
 use chrono_tz::Tz;
-use serde::{Deserialize, Serialize};
-use tauri::AppHandle;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, Runtime, State};
 use tauri_plugin_store::StoreExt;
 
+use crate::calendars::{self, Calendar, CalendarDate};
+use crate::clipboard;
+use crate::epochs::Epoch;
+use crate::error::TimestampError;
+use crate::ids::{self, IdKind, SnowflakeConfig};
+use crate::fiscal::FiscalCalendar;
+use crate::history::{self, CopyContext};
+use crate::locale::Locale;
+use crate::markup;
+use crate::presets;
+use crate::relative::{self, Granularity};
+use crate::template::{Diagnostic, Field, Template, Token, MODIFIERS, STRFTIME_PREFIX, TOKENS};
+use crate::seasons::{self, Hemisphere, SeasonMode};
+use crate::weeks::{self, WeekStart};
+use crate::zones::{self, ResolvedZone};
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct TimestampFormat {
     pub name: String,
     pub format: String,
     pub value: String,
+    pub timezone: ResolvedZone,
+    /// Why this entry couldn't be rendered, in which case `value` is empty.
+    /// One broken preset doesn't take the rest of the list with it.
+    #[serde(skip_serializing_if = "Option::is_none", skip_deserializing)]
+    pub error: Option<TimestampError>,
+}
+
+/// A rendered value together with the zone it was rendered in.
+#[derive(Serialize, Clone, Debug)]
+pub struct RenderedTimestamp {
+    pub value: String,
+    pub timezone: ResolvedZone,
+}
+
+/// An explicit point in time supplied by the caller instead of "now".
+///
+/// Serialized as `{ "kind": "epochMillis", "value": 1700000000000 }` etc.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "kind", content = "value", rename_all = "camelCase")]
+pub enum InstantInput {
+    Utc(DateTime<Utc>),
+    EpochSeconds(i64),
+    EpochMillis(i64),
+    Rfc3339(String),
+}
+
+impl InstantInput {
+    pub fn resolve(&self) -> Result<DateTime<Utc>, TimestampError> {
+        let invalid = |message: String| TimestampError::InvalidInstant { message };
+        match self {
+            InstantInput::Utc(dt) => Ok(*dt),
+            InstantInput::EpochSeconds(s) => DateTime::from_timestamp(*s, 0)
+                .ok_or_else(|| invalid(format!("Epoch seconds out of range: {}", s))),
+            InstantInput::EpochMillis(ms) => DateTime::from_timestamp_millis(*ms)
+                .ok_or_else(|| invalid(format!("Epoch milliseconds out of range: {}", ms))),
+            InstantInput::Rfc3339(s) => DateTime::parse_from_rfc3339(s.trim())
+                .map(|dt| dt.with_timezone(&Utc))
+                .map_err(|e| invalid(format!("Invalid RFC 3339 timestamp '{}': {}", s, e))),
+        }
+    }
+}
+
+/// `{relative}` settings for a single `format_instant` call.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RelativeArgs {
+    pub reference: Option<InstantInput>,
+    pub granularity: Option<Granularity>,
 }
 
+/// Unknown timezones and malformed formats are errors unless `lenient` is set
+/// (or the `lenientFormats` preference is on), in which case they fall back as
+/// they always have.
 #[tauri::command]
-pub fn generate_timestamp(format: String, timezone: Option<String>) -> Result<String, String> {
+pub fn generate_timestamp(
+    app: AppHandle,
+    cache: State<'_, FormatCache>,
+    format: String,
+    timezone: Option<String>,
+    lenient: Option<bool>,
+) -> Result<RenderedTimestamp, TimestampError> {
+    let lenient = is_lenient(&app, lenient);
+    let (tz, zone) = resolve_zone(&app, timezone, lenient)?;
+    let now = Utc::now();
+    let opts = RenderOptions::from_prefs(&app, None);
     // For backward-compat: support simple keywords
-    let now = Local::now();
-    match format.as_str() {
-        "unix" => return Ok(now.timestamp().to_string()),
-        "unix_ms" => return Ok(now.timestamp_millis().to_string()),
-        "iso" => return Ok(now.to_rfc3339()),
-        "rfc2822" => return Ok(now.to_rfc2822()),
-        "date" => return Ok(now.format("%Y-%m-%d").to_string()),
-        "time" => return Ok(now.format("%H:%M:%S").to_string()),
-        "datetime" => return Ok(now.format("%Y-%m-%d %H:%M:%S").to_string()),
-        _ => {}
-    }
-
-    let tz_opt: Option<Tz> = timezone
-        .as_deref()
-        .unwrap_or("")
-        .parse::<Tz>()
-        .ok();
-    let formatted = format_with_tokens(&format, tz_opt).unwrap_or_else(|_| "Invalid format".to_string());
-    Ok(formatted)
+    let value = match format_keyword(&format, now.with_timezone(&tz), &opts) {
+        Some(value) => value?,
+        None => cache.get_checked(&format, lenient)?.render(now, tz, &opts)?,
+    };
+    Ok(RenderedTimestamp { value, timezone: zone })
 }
 
+/// Render `format` (a keyword or token string) for an explicit instant rather than now.
+/// `{relative}` is measured from `relative.reference`, or from now when it is omitted.
 #[tauri::command]
-pub fn get_current_formats(app: AppHandle) -> Vec<TimestampFormat> {
-    let now = Local::now();
-    let mut out = vec![
-        TimestampFormat {
-            name: "Unix Timestamp".to_string(),
-            format: "unix".to_string(),
-            value: now.timestamp().to_string(),
-        },
-        TimestampFormat {
-            name: "Unix Milliseconds".to_string(),
-            format: "unix_ms".to_string(),
-            value: now.timestamp_millis().to_string(),
-        },
-        TimestampFormat {
-            name: "ISO 8601".to_string(),
-            format: "iso".to_string(),
-            value: now.to_rfc3339(),
-        },
-        TimestampFormat {
-            name: "RFC 2822".to_string(),
-            format: "rfc2822".to_string(),
-            value: now.to_rfc2822(),
-        },
-        TimestampFormat {
-            name: "Date Only".to_string(),
-            format: "date".to_string(),
-            value: now.format("%Y-%m-%d").to_string(),
-        },
-        TimestampFormat {
-            name: "Time Only".to_string(),
-            format: "time".to_string(),
-            value: now.format("%H:%M:%S").to_string(),
-        },
-    ];
+#[allow(clippy::too_many_arguments)]
+pub fn format_instant(
+    app: AppHandle,
+    instant: InstantInput,
+    format: String,
+    timezone: Option<String>,
+    locale: Option<String>,
+    relative: Option<RelativeArgs>,
+    fiscal: Option<FiscalCalendar>,
+    lenient: Option<bool>,
+) -> Result<RenderedTimestamp, TimestampError> {
+    let lenient = is_lenient(&app, lenient);
+    let at = instant.resolve()?;
+    let (tz, zone) = resolve_zone(&app, timezone, lenient)?;
+    let mut opts = RenderOptions::from_prefs(&app, locale).with_fiscal(fiscal)?;
+    if let Some(value) = format_keyword(&format, at.with_timezone(&tz), &opts) {
+        return Ok(RenderedTimestamp { value: value?, timezone: zone });
+    }
+    let relative = relative.unwrap_or_default();
+    opts.reference = relative.reference.map(|r| r.resolve()).transpose()?;
+    if let Some(g) = relative.granularity {
+        opts.granularity = g;
+    }
+    let compiled = app.state::<FormatCache>().get_checked(&format, lenient)?;
+    Ok(RenderedTimestamp { value: compiled.render(at, tz, &opts)?, timezone: zone })
+}
 
-    // Load default format and timezone from store if available
-    if let Ok(store) = app.store("prefs.json") {
-        let default_format = match store.get("defaultFormat") {
-            Some(v) => v.as_str().map(|s| s.to_string()),
-            _ => None,
-        };
-        let timezone = match store.get("timezone") {
-            Some(v) => v.as_str().map(|s| s.to_string()),
-            _ => None,
-        };
-        if let Some(fmt) = default_format {
-            let formatted = format_with_tokens(&fmt, timezone.and_then(|t| t.parse::<Tz>().ok()))
-                .unwrap_or_else(|_| "Invalid format".to_string());
-            out.insert(0, TimestampFormat { name: "Default Format".into(), format: fmt.clone(), value: formatted });
+/// The keywords `format_keyword` renders with chrono directly.
+const CLOCK_KEYWORDS: &[&str] = &["iso", "rfc2822", "date", "time", "datetime"];
+
+/// Whether `format` is one of the keywords rather than a token format.
+/// Checked against the keyword lists, without rendering anything.
+pub(crate) fn is_keyword(format: &str) -> bool {
+    CLOCK_KEYWORDS.contains(&format)
+        || Epoch::from_keyword(format).is_some()
+        || IdKind::from_keyword(format).is_some()
+        || markup::is_keyword(format)
+}
+
+/// Legacy keyword formats shared by `generate_timestamp` and `format_instant`.
+/// `None` when `keyword` isn't one.
+fn format_keyword<T: TimeZone>(
+    keyword: &str,
+    at: DateTime<T>,
+    opts: &RenderOptions,
+) -> Option<Result<String, TimestampError>>
+where
+    T::Offset: std::fmt::Display,
+{
+    Some(Ok(match keyword {
+        "iso" => at.to_rfc3339(),
+        "rfc2822" => at.to_rfc2822(),
+        "date" => at.format("%Y-%m-%d").to_string(),
+        "time" => at.format("%H:%M:%S").to_string(),
+        "datetime" => at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        _ => {
+            let utc = at.with_timezone(&Utc);
+            if let Some(epoch) = Epoch::from_keyword(keyword) {
+                return Some(epoch.encode(utc, at.naive_local()).map_err(|message| TimestampError::InvalidInstant { message }));
+            }
+            return IdKind::from_keyword(keyword)
+                .map(|id| id.generate(utc, &opts.snowflake))
+                .or_else(|| markup::render(keyword, &at.fixed_offset(), opts.locale))
+                .map(Ok);
         }
+    }))
+}
+
+const KEYWORD_FORMATS: &[(&str, &str)] = &[
+    ("Unix Timestamp", "unix"),
+    ("Unix Milliseconds", "unix_ms"),
+    ("ISO 8601", "iso"),
+    ("RFC 2822", "rfc2822"),
+    ("Date Only", "date"),
+    ("Time Only", "time"),
+    ("Discord", "discord"),
+    ("Discord Relative", "discord_R"),
+    ("Slack", "slack"),
+    ("HTML <time>", "html"),
+    ("Org-mode", "org"),
+    ("Markdown Front Matter", "markdown"),
+];
+
+/// Read a preference from `prefs.json`, ignoring values of the wrong shape.
+pub(crate) fn stored_pref<T: DeserializeOwned>(app: &AppHandle<impl Runtime>, key: &str) -> Option<T> {
+    let store = app.store("prefs.json").ok()?;
+    store.get(key).and_then(|v| serde_json::from_value(v).ok())
+}
+
+/// Resolve the zone for a command using the shared policy in `zones::resolve`.
+pub(crate) fn resolve_zone(app: &AppHandle, explicit: Option<String>, lenient: bool) -> Result<(Tz, ResolvedZone), TimestampError> {
+    zones::resolve(explicit.as_deref(), stored_pref::<String>(app, "timezone").as_deref(), lenient)
+}
+
+/// Lenient mode is opt-in: per call, or for every call via the
+/// `lenientFormats` preference for presets written before strict checking.
+pub(crate) fn is_lenient(app: &AppHandle, lenient: Option<bool>) -> bool {
+    lenient.or_else(|| stored_pref(app, "lenientFormats")).unwrap_or(false)
+}
+
+/// `locale`, `timezone` and `fiscal` override the stored preferences for this
+/// call. Presets follow the keyword formats, in their own zone and locale
+/// where they set one.
+#[tauri::command]
+pub fn get_current_formats(
+    app: AppHandle,
+    locale: Option<String>,
+    timezone: Option<String>,
+    fiscal: Option<FiscalCalendar>,
+    lenient: Option<bool>,
+) -> Result<Vec<TimestampFormat>, TimestampError> {
+    let lenient = is_lenient(&app, lenient);
+    let now = Utc::now();
+    let (tz, zone) = resolve_zone(&app, timezone.clone(), lenient)?;
+    let opts = RenderOptions::from_prefs(&app, locale.clone()).with_fiscal(fiscal)?;
+    let mut out: Vec<TimestampFormat> = KEYWORD_FORMATS
+        .iter()
+        .filter_map(|(name, keyword)| {
+            format_keyword(keyword, now.with_timezone(&tz), &opts).map(|value| {
+                let (value, error) = match value {
+                    Ok(value) => (value, None),
+                    Err(e) => (String::new(), Some(e)),
+                };
+                TimestampFormat { name: name.to_string(), format: keyword.to_string(), value, timezone: zone.clone(), error }
+            })
+        })
+        .collect();
+
+    // Load default format from store if available
+    if let Some(fmt) = stored_pref::<String>(&app, "defaultFormat") {
+        let rendered = app.state::<FormatCache>().get_checked(&fmt, lenient).and_then(|c| c.render(now, tz, &opts));
+        let (value, error) = match rendered {
+            Ok(value) => (value, None),
+            Err(e) => (String::new(), Some(e)),
+        };
+        let timezone = zone.clone();
+        out.insert(0, TimestampFormat { name: "Default Format".into(), format: fmt, value, timezone, error });
+    }
+
+    for preset in presets::load(&app) {
+        let rendered = render_preset(&app, &preset, now, timezone.clone(), locale.clone(), fiscal, lenient);
+        out.push(match rendered {
+            Ok(r) => TimestampFormat { name: preset.name, format: preset.format, value: r.value, timezone: r.timezone, error: None },
+            Err(e) => TimestampFormat {
+                name: preset.name,
+                format: preset.format,
+                value: String::new(),
+                timezone: zone.clone(),
+                error: Some(e),
+            },
+        });
     }
 
-    out
+    Ok(out)
+}
+
+/// `preset` at `at`, in its own zone and locale where it sets them and the
+/// call's otherwise.
+fn render_preset(
+    app: &AppHandle,
+    preset: &presets::Preset,
+    at: DateTime<Utc>,
+    timezone: Option<String>,
+    locale: Option<String>,
+    fiscal: Option<FiscalCalendar>,
+    lenient: bool,
+) -> Result<RenderedTimestamp, TimestampError> {
+    let (tz, zone) = resolve_zone(app, preset.timezone.clone().or(timezone), lenient)?;
+    let opts = RenderOptions::from_prefs(app, preset.locale.clone().or(locale)).with_fiscal(fiscal)?;
+    let value = match format_keyword(&preset.format, at.with_timezone(&tz), &opts) {
+        Some(value) => value?,
+        None => app.state::<FormatCache>().get_checked(&preset.format, lenient)?.render(at, tz, &opts)?,
+    };
+    Ok(RenderedTimestamp { value, timezone: zone })
+}
+
+/// `locale` and `fiscal` override the stored preferences for this call.
+#[tauri::command]
+pub fn format_tokens(
+    app: AppHandle,
+    cache: State<'_, FormatCache>,
+    format: String,
+    timezone: Option<String>,
+    locale: Option<String>,
+    fiscal: Option<FiscalCalendar>,
+    lenient: Option<bool>,
+) -> Result<RenderedTimestamp, TimestampError> {
+    let lenient = is_lenient(&app, lenient);
+    let (tz, zone) = resolve_zone(&app, timezone, lenient)?;
+    let compiled = cache.get_checked(&format, lenient)?;
+    let opts = RenderOptions::from_prefs(&app, locale).with_fiscal(fiscal)?;
+    Ok(RenderedTimestamp { value: compiled.render(Utc::now(), tz, &opts)?, timezone: zone })
+}
+
+/// Parse `format` without rendering it and report every problem with its position.
+#[tauri::command]
+pub fn validate_format(format: String) -> Vec<Diagnostic> {
+    Template::parse(&format).diagnostics
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TokenDoc {
+    pub name: String,
+    pub category: String,
+    pub description: String,
+    pub example: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ModifierDoc {
+    pub syntax: String,
+    pub description: String,
 }
 
+#[derive(Serialize, Clone, Debug)]
+pub struct TokenReference {
+    pub tokens: Vec<TokenDoc>,
+    pub modifiers: Vec<ModifierDoc>,
+}
+
+/// Token and modifier reference for the docs page, generated from the
+/// registry in `template.rs`. Examples are rendered now, in the user's locale.
 #[tauri::command]
-pub fn format_tokens(format: String, timezone: Option<String>) -> Result<String, String> {
-    let tz = timezone.and_then(|t| t.parse::<Tz>().ok());
-    format_with_tokens(&format, tz)
+pub fn list_tokens(app: AppHandle, timezone: Option<String>, locale: Option<String>) -> Result<TokenReference, TimestampError> {
+    let (tz, _) = resolve_zone(&app, timezone, is_lenient(&app, None))?;
+    let opts = RenderOptions::from_prefs(&app, locale);
+    let now = Utc::now().with_timezone(&tz);
+    let tokens = TOKENS
+        .iter()
+        .map(|t| TokenDoc {
+            name: t.name.to_string(),
+            category: t.category.to_string(),
+            description: t.description.to_string(),
+            example: token_value(t.token, None, false, &now, &opts),
+        })
+        .chain(std::iter::once(TokenDoc {
+            name: format!("{}%a %d %b", STRFTIME_PREFIX),
+            category: "Advanced".into(),
+            description: "Any chrono strftime pattern".into(),
+            example: now.format("%a %d %b").to_string(),
+        }))
+        .collect();
+    let modifiers = MODIFIERS
+        .iter()
+        .map(|(syntax, description)| ModifierDoc { syntax: syntax.to_string(), description: description.to_string() })
+        .collect();
+    Ok(TokenReference { tokens, modifiers })
 }
 
+/// Copy `text` and add it to the history, described by `context`.
 #[tauri::command]
 pub fn copy_to_clipboard(
     app: AppHandle,
     text: String,
-) -> Result<(), String> {
-    use tauri_plugin_clipboard_manager::ClipboardExt;
-    
-    app.clipboard()
-        .write_text(text)
-        .map_err(|e| e.to_string())
+    context: Option<CopyContext>,
+) -> Result<(), TimestampError> {
+    clipboard::write(&app, &text, None)?;
+    history::record(&app, &text, context.unwrap_or_default());
+    Ok(())
 }
 
-fn ordinal_suffix(n: u32) -> &'static str {
-    if (11..=13).contains(&(n % 100)) {
-        "th"
-    } else {
-        match n % 10 {
-            1 => "st",
-            2 => "nd",
-            3 => "rd",
-            _ => "th",
-        }
+/// A format string parsed once and rendered many times. Rendering only
+/// computes the values of tokens the template actually contains.
+#[derive(Debug)]
+pub struct CompiledFormat {
+    template: Template,
+}
+
+impl CompiledFormat {
+    pub fn compile(fmt: &str) -> CompiledFormat {
+        CompiledFormat { template: Template::parse(fmt) }
+    }
+
+    /// Fails only for encodings that can't represent `at`.
+    pub fn render(&self, at: DateTime<Utc>, tz: Tz, opts: &RenderOptions) -> Result<String, TimestampError> {
+        let dt = at.with_timezone(&tz);
+        let mut error = None;
+        let value = self.template.render_with(|field, zone| {
+            let zoned;
+            let dt = match zone {
+                Some(zone) => {
+                    zoned = at.with_timezone(&zone);
+                    &zoned
+                }
+                None => &dt,
+            };
+            match field {
+                Field::Token { token: Token::Epoch(epoch), .. } => {
+                    epoch.encode(dt.with_timezone(&Utc), dt.naive_local()).unwrap_or_else(|message| {
+                        error.get_or_insert(TimestampError::InvalidInstant { message });
+                        String::new()
+                    })
+                }
+                Field::Token { token, calendar, in_date } => token_value(token, calendar, in_date, dt, opts),
+                Field::Strftime(items) => strftime(dt, items),
+            }
+        });
+        error.map_or(Ok(value), Err)
+    }
+}
+
+/// Per-call settings that affect rendering but not parsing.
+pub struct RenderOptions {
+    pub locale: &'static Locale,
+    /// Instant `{relative}` is measured from; `None` means the time of rendering.
+    pub reference: Option<DateTime<Utc>>,
+    pub granularity: Granularity,
+    pub week_start: WeekStart,
+    pub season_mode: SeasonMode,
+    pub hemisphere: Hemisphere,
+    pub fiscal: FiscalCalendar,
+    pub snowflake: SnowflakeConfig,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions::for_locale(None)
     }
 }
 
-fn season(month0: u32) -> &'static str {
-    match month0 {
-        2..=4 => "Spring",
-        5..=7 => "Summer",
-        8..=10 => "Fall",
-        _ => "Winter",
+impl RenderOptions {
+    pub fn for_locale(tag: Option<&str>) -> RenderOptions {
+        RenderOptions {
+            locale: Locale::resolve(tag.unwrap_or("en-US")),
+            reference: None,
+            granularity: Granularity::default(),
+            week_start: WeekStart::default(),
+            season_mode: SeasonMode::default(),
+            hemisphere: Hemisphere::default(),
+            fiscal: FiscalCalendar::default(),
+            snowflake: SnowflakeConfig::default(),
+        }
+    }
+
+    /// Options from the stored preferences, with `locale` overriding the stored one.
+    fn from_prefs(app: &AppHandle, locale: Option<String>) -> RenderOptions {
+        let mut opts = RenderOptions::for_locale(locale.or_else(|| stored_pref(app, "locale")).as_deref());
+        if let Some(g) = stored_pref(app, "relativeGranularity") {
+            opts.granularity = g;
+        }
+        if let Some(w) = stored_pref(app, "weekStart") {
+            opts.week_start = w;
+        }
+        if let Some(m) = stored_pref(app, "seasonMode") {
+            opts.season_mode = m;
+        }
+        if let Some(h) = stored_pref(app, "hemisphere") {
+            opts.hemisphere = h;
+        }
+        if let Some(f) = stored_pref::<FiscalCalendar>(app, "fiscal").filter(|f| f.validate().is_ok()) {
+            opts.fiscal = f;
+        }
+        opts.snowflake = ids::snowflake_pref(app);
+        opts
+    }
+
+    /// Apply a per-call fiscal calendar; unlike the stored preference, an
+    /// invalid one is an error rather than ignored.
+    fn with_fiscal(mut self, fiscal: Option<FiscalCalendar>) -> Result<RenderOptions, TimestampError> {
+        if let Some(f) = fiscal {
+            f.validate().map_err(|message| TimestampError::InvalidSetting { name: "fiscal".into(), message })?;
+            self.fiscal = f;
+        }
+        Ok(self)
     }
 }
 
-fn week_number(date: chrono::NaiveDate) -> u32 {
-    // ISO weeks but non-iso variant can differ; use chrono iso week for both for simplicity
-    date.iso_week().week()
+/// Compiled formats keyed by format string, shared as managed state so the
+/// widget's once-a-second refresh doesn't re-parse its presets.
+#[derive(Default)]
+pub struct FormatCache {
+    formats: Mutex<HashMap<String, Arc<CompiledFormat>>>,
 }
 
-pub(crate) fn format_with_tokens(fmt: &str, tz: Option<Tz>) -> Result<String, String> {
-    // base date now in UTC, then convert
-    let now_utc = Utc::now();
-    
-    // Handle timezone conversion - use dynamic dispatch to work with any timezone
-    let (year, month0, day, weekday, hour24, minute, second, millis) = match tz {
-        Some(tz) => {
-            let dt = tz.from_utc_datetime(&now_utc.naive_utc());
-            (dt.year(), dt.month0(), dt.day(), dt.weekday().num_days_from_sunday() as usize,
-             dt.hour(), dt.minute(), dt.second(), dt.timestamp_subsec_millis())
+impl FormatCache {
+    // Formats typed into the preferences page pass through here one keystroke
+    // at a time; start over rather than grow without bound.
+    const MAX_ENTRIES: usize = 128;
+
+    pub fn get(&self, fmt: &str) -> Arc<CompiledFormat> {
+        let mut formats = self.formats.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(compiled) = formats.get(fmt) {
+            return compiled.clone();
         }
-        None => {
-            let dt = now_utc;
-            (dt.year(), dt.month0(), dt.day(), dt.weekday().num_days_from_sunday() as usize,
-             dt.hour(), dt.minute(), dt.second(), dt.timestamp_subsec_millis())
+        if formats.len() >= Self::MAX_ENTRIES {
+            formats.clear();
         }
-    };
-    
-    let month = month0 + 1;
-    
-    let days = [
-        "Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday",
-    ];
-    let days_abb = ["Sun.", "Mon.", "Tue.", "Wed.", "Thu.", "Fri.", "Sat."];
-    let months = [
-        "January", "February", "March", "April", "May", "June", "July", "August", "September", "October", "November", "December",
-    ];
-    let months_abb = [
-        "Jan.", "Feb.", "Mar.", "Apr.", "May", "Jun.", "Jul.", "Aug.", "Sep.", "Oct.", "Nov.", "Dec.",
-    ];
-
-    let mut hour12 = hour24 as i32;
-    let period = if hour12 >= 12 { "PM" } else { "AM" };
-    if hour12 > 12 { hour12 -= 12; }
-    else if hour12 == 0 { hour12 = 12; }
-    let time12 = format!("{}:{:02}", hour12, minute);
-    let time24 = format!("{:02}:{:02}", hour24, minute);
-
-    let quarter = ((month - 1) / 3) + 1;
-    let season = season(month0);
-    
-    // Calculate week numbers using a consistent date
-    let naive_date = match tz {
-        Some(tz) => tz.from_utc_datetime(&now_utc.naive_utc()).date_naive(),
-        None => now_utc.date_naive(),
-    };
-    let week = week_number(naive_date);
-    let iso_week = naive_date.iso_week().week();
-    let day_ordinal = format!("{}{}", day, ordinal_suffix(day));
-
-    // timezone abbreviation and offset
-    let (tz_abbr, tz_full, utc_offset) = if let Some(tz) = tz {
-        let offset = tz.offset_from_utc_datetime(&now_utc.naive_utc());
-        let total = offset.fix().local_minus_utc(); // seconds
-        let sign = if total >= 0 { '+' } else { '-' };
-        let total = total.abs();
-        let hours = total / 3600;
-        let minutes = (total % 3600) / 60;
-        let utc_offset = format!("{}{:02}:{:02}", sign, hours, minutes);
-        // chrono-tz doesn't expose short names easily; approximate from tz name
-        let abbr = tz.name();
-        (abbr, tz.name(), utc_offset)
-    } else {
-        ("UTC", "UTC", "+00:00".to_string())
-    };
+        let compiled = Arc::new(CompiledFormat::compile(fmt));
+        formats.insert(fmt.to_string(), compiled.clone());
+        compiled
+    }
 
-    // Relative time - for now just use "just now" since we're using current time
-    let rel = "just now".to_string();
-
-    let mut out = fmt.to_string();
-    // Replace longest tokens first to avoid partial overlaps
-    let replacements: &[(&str, String)] = &[
-        ("{day-abb}", days_abb[weekday].to_string()),
-        ("{month-abb}", months_abb[month0 as usize].to_string()),
-        ("{day}", days[weekday].to_string()),
-        ("{month}", months[month0 as usize].to_string()),
-        ("{date}", format!("{}", day)),
-        ("{time}", time12.clone()),
-        ("{period}", period.to_string()),
-        ("{year}", format!("{}", year)),
-        ("{time24}", time24.clone()),
-        ("{seconds}", format!("{:02}", second)),
-        ("{milliseconds}", format!("{:03}", millis)),
-        ("{hours}", format!("{}", hour12)),
-        ("{hours24}", format!("{}", hour24)),
-        ("{minutes}", format!("{:02}", minute)),
-        ("{day-ordinal}", day_ordinal),
-        ("{month-num}", format!("{}", month)),
-        ("{month-num-pad}", format!("{:02}", month)),
-        ("{year-short}", format!("{:02}", year % 100)),
-        ("{week}", format!("{}", week)),
-        ("{iso-week}", format!("{}", iso_week)),
-        ("{quarter}", format!("{}", quarter)),
-        ("{season}", season.to_string()),
-        ("{timezone}", tz_abbr.to_string()),
-        ("{utc-offset}", utc_offset.clone()),
-        ("{timezone-full}", tz_full.to_string()),
-    ("{relative}", rel),
-    ];
-
-    for (k, v) in replacements {
-        out = out.replace(k, v);
+    /// Like `get`, but a format with diagnostics is an error unless `lenient`.
+    pub fn get_checked(&self, fmt: &str, lenient: bool) -> Result<Arc<CompiledFormat>, TimestampError> {
+        let compiled = self.get(fmt);
+        if lenient || compiled.template.diagnostics.is_empty() {
+            return Ok(compiled);
+        }
+        Err(TimestampError::InvalidFormat {
+            format: fmt.to_string(),
+            diagnostics: compiled.template.diagnostics.clone(),
+        })
     }
+}
 
-    Ok(out)
+/// chrono reports unsupported specifiers by failing `Display`; turn that
+/// into an empty value rather than a panic in `to_string`.
+fn strftime(dt: &DateTime<Tz>, items: &[Item<'static>]) -> String {
+    use std::fmt::Write;
+    let mut out = String::new();
+    match write!(out, "{}", dt.format_with_items(items.iter())) {
+        Ok(()) => out,
+        Err(_) => String::new(),
+    }
+}
+
+/// `in_date` picks the locale's in-date month form for `{month}`.
+fn token_value(token: Token, calendar: Option<Calendar>, in_date: bool, dt: &DateTime<Tz>, opts: &RenderOptions) -> String {
+    let locale = opts.locale;
+    // Dates a calendar doesn't cover (Japanese before Meiji, Persian outside
+    // 560-3798) fall through to the Gregorian values below.
+    if let Some(date) = calendar.and_then(|c| calendars::convert(c, dt.date_naive())) {
+        if let Some(value) = calendar_value(token, &date, in_date, opts) {
+            return value;
+        }
+    }
+    let weekday = || dt.weekday().num_days_from_sunday() as usize;
+    match token {
+        Token::DayAbb => locale.days_abb[weekday()].to_string(),
+        Token::MonthAbb => locale.months_abb[dt.month0() as usize].to_string(),
+        Token::Day => locale.days[weekday()].to_string(),
+        Token::Month => locale.month(dt.month0() as usize, in_date).to_string(),
+        Token::Date => dt.day().to_string(),
+        Token::Time => format!("{}:{:02}", dt.hour12().1, dt.minute()),
+        Token::TimeLocale => locale.clock(dt.hour(), dt.minute()),
+        Token::Period => locale.period(dt.hour12().0).to_string(),
+        Token::Year => dt.year().to_string(),
+        Token::Era => calendars::convert(Calendar::Gregorian, dt.date_naive()).map_or("", |d| d.era).to_string(),
+        Token::Time24 => format!("{:02}:{:02}", dt.hour(), dt.minute()),
+        Token::Seconds => format!("{:02}", dt.second()),
+        Token::Milliseconds => format!("{:03}", dt.timestamp_subsec_millis()),
+        Token::Hours => dt.hour12().1.to_string(),
+        Token::Hours24 => dt.hour().to_string(),
+        Token::Minutes => format!("{:02}", dt.minute()),
+        Token::DayOrdinal => locale.ordinal(dt.day()),
+        Token::MonthNum => dt.month().to_string(),
+        Token::MonthNumPad => format!("{:02}", dt.month()),
+        Token::YearShort => format!("{:02}", dt.year() % 100),
+        Token::Week => weeks::week_of_year(dt.date_naive(), opts.week_start).to_string(),
+        Token::IsoWeek => dt.iso_week().week().to_string(),
+        Token::IsoWeekYear => weeks::iso_week_year(dt.date_naive()).to_string(),
+        Token::WeekOfMonth => weeks::week_of_month(dt.date_naive(), opts.week_start).to_string(),
+        Token::DayOfYear => dt.ordinal().to_string(),
+        Token::DaysLeftInYear => (days_in_year(dt.year()) - dt.ordinal()).to_string(),
+        Token::IsoOrdinal => format!("{:04}-{:03}", dt.year(), dt.ordinal()),
+        Token::JulianDay => julian_day(dt.date_naive()).to_string(),
+        Token::ModifiedJulianDay => (julian_day(dt.date_naive()) - MJD_EPOCH).to_string(),
+        Token::Quarter => (dt.month0() / 3 + 1).to_string(),
+        Token::Season => seasons::season_at(dt, opts.season_mode, opts.hemisphere).0.name(opts.locale).to_string(),
+        Token::SeasonDay => seasons::season_day(dt, opts.season_mode, opts.hemisphere, opts.locale),
+        Token::FiscalYear => fiscal(dt, opts).year.to_string(),
+        Token::FiscalQuarter => fiscal(dt, opts).quarter.to_string(),
+        Token::FiscalPeriod => fiscal(dt, opts).period.to_string(),
+        Token::FiscalWeek => fiscal(dt, opts).week.to_string(),
+        Token::Timezone => zones::abbreviation(dt),
+        Token::UtcOffset => zones::utc_offset(dt),
+        Token::TimezoneFull => dt.timezone().name().to_string(),
+        Token::TimezoneLong => zones::long_name(dt),
+        // `CompiledFormat::render` encodes these itself to report errors.
+        Token::Epoch(e) => e.encode(dt.with_timezone(&Utc), dt.naive_local()).unwrap_or_default(),
+        Token::Id(kind) => kind.generate(dt.with_timezone(&Utc), &opts.snowflake),
+        Token::Relative => relative::describe(dt, opts.reference.unwrap_or_else(Utc::now), opts.granularity, locale),
+    }
+}
+
+fn fiscal(dt: &DateTime<Tz>, opts: &RenderOptions) -> crate::fiscal::FiscalDate {
+    opts.fiscal.locate(dt.date_naive(), opts.week_start.first_day())
+}
+
+/// Julian Day Number of 0000-12-31, the day before chrono's day 1 of the common era.
+const JDN_CE_OFFSET: i64 = 1_721_425;
+/// Julian Day Number minus Modified Julian Day, for dates (MJD starts at midnight).
+pub(crate) const MJD_EPOCH: i64 = 2_400_001;
+
+/// Julian Day Number: whole days since noon, 1 January 4713 BC (proleptic Julian).
+pub(crate) fn julian_day(date: NaiveDate) -> i64 {
+    i64::from(date.num_days_from_ce()) + JDN_CE_OFFSET
+}
+
+pub(crate) fn from_julian_day(jdn: i64) -> Option<NaiveDate> {
+    NaiveDate::from_num_days_from_ce_opt(i32::try_from(jdn - JDN_CE_OFFSET).ok()?)
 }
 
-fn humanize_duration(d: Duration) -> String {
-    let secs = d.num_seconds().abs();
-    if secs < 60 { return "just now".into(); }
-    let mins = secs / 60;
-    if mins < 60 { return format!("{} minute{} ago", mins, if mins==1 {""} else {"s"}); }
-    let hours = mins / 60;
-    if hours < 24 { return format!("{} hour{} ago", hours, if hours==1 {""} else {"s"}); }
-    let days = hours / 24;
-    format!("{} day{} ago", days, if days==1 {""} else {"s"})
+fn days_in_year(year: i32) -> u32 {
+    if NaiveDate::from_ymd_opt(year, 2, 29).is_some() { 366 } else { 365 }
+}
+
+/// Tokens that follow an `@calendar` override; the rest render as usual.
+/// Transliterated month names have no standard abbreviation, so `{month-abb}`
+/// uses the full name.
+fn calendar_value(token: Token, date: &CalendarDate, in_date: bool, opts: &RenderOptions) -> Option<String> {
+    let locale = opts.locale;
+    let month = || date.month_name.unwrap_or_else(|| locale.month(date.month as usize - 1, in_date));
+    Some(match token {
+        Token::Year => date.year.to_string(),
+        Token::YearShort => format!("{:02}", date.year.rem_euclid(100)),
+        Token::Era => date.era.to_string(),
+        Token::Month => month().to_string(),
+        Token::MonthAbb => match date.month_name {
+            Some(name) => name.to_string(),
+            None => locale.months_abb[date.month as usize - 1].to_string(),
+        },
+        Token::MonthNum => date.month.to_string(),
+        Token::MonthNumPad => format!("{:02}", date.month),
+        Token::Date => date.day.to_string(),
+        Token::DayOrdinal => locale.ordinal(date.day),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(fmt: &str, y: i32, m: u32, d: u32) -> String {
+        let at = NaiveDate::from_ymd_opt(y, m, d).unwrap().and_hms_opt(12, 0, 0).unwrap().and_utc();
+        CompiledFormat::compile(fmt).render(at, Tz::UTC, &RenderOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn ordinal_tokens_around_leap_years() {
+        let fmt = "{day-of-year} {days-left-in-year} {iso-ordinal} {julian-day} {modified-julian-day}";
+        assert_eq!(render(fmt, 2000, 2, 29), "60 306 2000-060 2451604 51603");
+        // 1900 is not a leap year, so March 1 is day 60 as well.
+        assert_eq!(render(fmt, 1900, 3, 1), "60 305 1900-060 2415080 15079");
+        assert_eq!(render(fmt, 2100, 12, 31), "365 0 2100-365 2488434 88433");
+        assert_eq!(render(fmt, 2024, 12, 31), "366 0 2024-366 2460676 60675");
+    }
+
+    #[test]
+    fn calendars_fall_back_to_gregorian_out_of_range() {
+        let fmt = "{year@japanese} {era@japanese} {month@japanese}";
+        assert_eq!(render(fmt, 2019, 5, 1), "1 Reiwa May");
+        assert_eq!(render(fmt, 1800, 1, 1), "1800 AD January");
+        assert_eq!(render("{year@persian} {month@persian}", 300, 6, 1), "300 June");
+    }
+
+    #[test]
+    fn months_inflect_after_the_day() {
+        let at = NaiveDate::from_ymd_opt(2024, 11, 14).unwrap().and_hms_opt(12, 0, 0).unwrap().and_utc();
+        let render = |fmt: &str, tag: &str| {
+            CompiledFormat::compile(fmt).render(at, Tz::UTC, &RenderOptions::for_locale(Some(tag))).unwrap()
+        };
+        assert_eq!(render("{date} {month} {year}", "ru-RU"), "14 ноября 2024");
+        assert_eq!(render("{month} {year}", "ru-RU"), "ноябрь 2024");
+        assert_eq!(render("{date}. {month}", "pl-PL"), "14. listopada");
+        assert_eq!(render("{month}, {date}", "pl-PL"), "listopad, 14");
+        assert_eq!(render("{date} {month} {season}", "en-GB"), "14 November Autumn");
+        assert_eq!(render("{season}", "ru-RU"), "осень");
+    }
+
+    /// Timing comparison for the format cache, printed rather than asserted
+    /// tightly. Run with `cargo test --release -- --ignored --nocapture cache`.
+    #[test]
+    #[ignore]
+    fn cache_beats_parsing_each_render() {
+        use std::time::Instant;
+        let fmt = "{day}, {month} {day-ordinal} {year} {time} {period} {timezone} (week {week}, {relative})";
+        let (tz, opts, cache) = (chrono_tz::Europe::Paris, RenderOptions::default(), FormatCache::default());
+        let runs = 20_000;
+        let now = Utc::now();
+
+        let start = Instant::now();
+        for _ in 0..runs {
+            std::hint::black_box(CompiledFormat::compile(std::hint::black_box(fmt)).render(now, tz, &opts).unwrap());
+        }
+        let cold = start.elapsed() / runs;
+
+        let start = Instant::now();
+        for _ in 0..runs {
+            std::hint::black_box(cache.get_checked(std::hint::black_box(fmt), false).unwrap().render(now, tz, &opts).unwrap());
+        }
+        let cached = start.elapsed() / runs;
+
+        println!("parse + render: {:?}/render, cached: {:?}/render", cold, cached);
+        assert!(cached < cold);
+    }
+
+    #[test]
+    fn julian_day_round_trips() {
+        let j2000 = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
+        assert_eq!(julian_day(j2000), 2_451_545);
+        assert_eq!(from_julian_day(2_451_545), Some(j2000));
+        let mut date = NaiveDate::from_ymd_opt(1899, 12, 1).unwrap();
+        while date.year() < 2101 {
+            assert_eq!(from_julian_day(julian_day(date)), Some(date));
+            date += chrono::Duration::days(37);
+        }
+        assert_eq!(from_julian_day(i64::MAX), None);
+    }
 }
@@ -67,13 +67,24 @@ pub fn create_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
 }
 
 fn quick_copy_timestamp<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
-    let timestamp = chrono::Local::now().timestamp().to_string();
-    
-    // Copy to clipboard using the clipboard plugin
-    use tauri_plugin_clipboard_manager::ClipboardExt;
-    app.clipboard()
-        .write_text(timestamp)
+    let now = chrono::Local::now();
+    let timestamp = now.timestamp().to_string();
+
+    // The HTML <time> flavour only when the `copyAs` preference asks for it
+    let copy_as = crate::timestamp::stored_pref(app, "copyAs").unwrap_or_default();
+    let html = match copy_as {
+        crate::clipboard::CopyAs::Text => None,
+        crate::clipboard::CopyAs::Html => Some(crate::markup::time_element(&now.fixed_offset(), &timestamp)),
+    };
+    crate::clipboard::write(app, &timestamp, html.as_deref())
         .map_err(|e| tauri::Error::Anyhow(anyhow::anyhow!("Failed to copy to clipboard: {}", e)))?;
+    let context = crate::history::CopyContext {
+        timezone: crate::zones::system_zone().map(|tz| tz.name().to_string()),
+        instant: Some(crate::timestamp::InstantInput::Utc(now.to_utc())),
+        source: crate::history::CopySource::Tray,
+        ..Default::default()
+    };
+    crate::history::record(app, &timestamp, context);
     
     Ok(())
 }
@@ -0,0 +1,110 @@
+//! Week numbering for `{week}`, `{week-of-month}` and `{iso-week-year}`.
+//!
+//! Schemes disagree around New Year: 2027-01-01 is a Friday, so it is in US
+//! week 1, Monday-start week 1, and ISO week 53 of 2026.
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// How `{week}` numbers weeks, from the `weekStart` preference.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WeekStart {
+    /// ISO 8601: Monday start, week 1 contains the year's first Thursday.
+    #[default]
+    Iso,
+    /// US: Sunday start, week 1 contains January 1.
+    Sunday,
+    /// Monday start, week 1 contains January 1.
+    Monday,
+    /// Middle Eastern: Saturday start, week 1 contains January 1.
+    Saturday,
+}
+
+impl WeekStart {
+    pub(crate) fn first_day(self) -> Weekday {
+        match self {
+            WeekStart::Sunday => Weekday::Sun,
+            WeekStart::Saturday => Weekday::Sat,
+            WeekStart::Iso | WeekStart::Monday => Weekday::Mon,
+        }
+    }
+}
+
+/// Week of the year under `scheme`. ISO weeks can belong to the previous or
+/// next year; pair with [`iso_week_year`] when that matters.
+pub fn week_of_year(date: NaiveDate, scheme: WeekStart) -> u32 {
+    match scheme {
+        WeekStart::Iso => date.iso_week().week(),
+        _ => {
+            let jan1 = NaiveDate::from_ymd_opt(date.year(), 1, 1).unwrap_or(date);
+            (date.ordinal0() + days_into_week(jan1, scheme.first_day())) / 7 + 1
+        }
+    }
+}
+
+/// Week of the month, week 1 containing the 1st; weeks start on the
+/// scheme's first day (Monday for ISO).
+pub fn week_of_month(date: NaiveDate, scheme: WeekStart) -> u32 {
+    let first = date.with_day(1).unwrap_or(date);
+    (date.day0() + days_into_week(first, scheme.first_day())) / 7 + 1
+}
+
+/// The year an ISO week belongs to, e.g. 2026 for 2027-01-01.
+pub fn iso_week_year(date: NaiveDate) -> i32 {
+    date.iso_week().year()
+}
+
+fn days_into_week(date: NaiveDate, first_day: Weekday) -> u32 {
+    (7 + date.weekday().num_days_from_monday() - first_day.num_days_from_monday()) % 7
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn year_boundaries() {
+        // (date, ISO week, ISO week-year, US week, Saturday-start week)
+        let cases = [
+            (date(2020, 12, 31), 53, 2020, 53, 53),
+            (date(2021, 1, 1), 53, 2020, 1, 1),
+            (date(2024, 12, 30), 1, 2025, 53, 53),
+            (date(2027, 1, 1), 53, 2026, 1, 1),
+        ];
+        for (d, iso, iso_year, us, saturday) in cases {
+            assert_eq!(week_of_year(d, WeekStart::Iso), iso, "ISO week of {}", d);
+            assert_eq!(iso_week_year(d), iso_year, "ISO week-year of {}", d);
+            assert_eq!(week_of_year(d, WeekStart::Sunday), us, "US week of {}", d);
+            assert_eq!(week_of_year(d, WeekStart::Saturday), saturday, "Saturday-start week of {}", d);
+        }
+    }
+
+    #[test]
+    fn week_53_years() {
+        // ISO years starting on a Thursday, or leap years starting on a Wednesday.
+        for (d, year) in [(date(2015, 12, 31), 2015), (date(2020, 12, 31), 2020), (date(2026, 12, 31), 2026)] {
+            assert_eq!((week_of_year(d, WeekStart::Iso), iso_week_year(d)), (53, year));
+        }
+        assert_eq!(week_of_year(date(2025, 12, 31), WeekStart::Iso), 1);
+        // A leap year starting on Saturday reaches a partial week 54 in the US scheme.
+        assert_eq!(week_of_year(date(2000, 12, 31), WeekStart::Sunday), 54);
+        assert_eq!(week_of_year(date(2000, 12, 31), WeekStart::Saturday), 53);
+    }
+
+    #[test]
+    fn schemes_split_the_first_days() {
+        // 2022-01-01 is a Saturday: it opens a week only for Saturday starts.
+        let (sat, sun) = (date(2022, 1, 1), date(2022, 1, 2));
+        assert_eq!((week_of_year(sat, WeekStart::Saturday), week_of_year(sun, WeekStart::Saturday)), (1, 1));
+        assert_eq!((week_of_year(sat, WeekStart::Sunday), week_of_year(sun, WeekStart::Sunday)), (1, 2));
+        assert_eq!((week_of_year(sat, WeekStart::Monday), week_of_year(sun, WeekStart::Monday)), (1, 1));
+        assert_eq!((week_of_year(sat, WeekStart::Iso), iso_week_year(sat)), (52, 2021));
+        assert_eq!(week_of_month(date(2022, 1, 3), WeekStart::Monday), 2);
+        assert_eq!(week_of_month(date(2022, 1, 3), WeekStart::Saturday), 1);
+    }
+}
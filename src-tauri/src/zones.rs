@@ -0,0 +1,220 @@
+//! Timezone resolution and display names.
+//!
+//! Abbreviations come from the tz database via chrono-tz and are DST-correct
+//! for the instant being rendered. Many zones only have numeric
+//! abbreviations ("+04", "-03"); those get a "GMT+04:00" style long name.
+
+use chrono::{DateTime, Offset};
+use chrono_tz::{OffsetName, Tz};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{suggestions, TimestampError};
+
+/// Where the timezone used for a render came from.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ZoneSource {
+    Argument,
+    Preference,
+    System,
+    /// The system zone could not be determined; UTC was used.
+    Fallback,
+}
+
+/// The zone a command rendered in, reported back so the UI can show it.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedZone {
+    pub name: String,
+    pub source: ZoneSource,
+}
+
+/// The single timezone policy for every command: an explicit argument wins,
+/// then the stored `timezone` preference, then the system zone. Blank names
+/// count as absent. A name that doesn't parse is an error, unless `lenient`,
+/// in which case it is skipped.
+pub fn resolve(explicit: Option<&str>, stored: Option<&str>, lenient: bool) -> Result<(Tz, ResolvedZone), TimestampError> {
+    let parse = |name: Option<&str>| -> Result<Option<Tz>, TimestampError> {
+        match name.map(str::trim).filter(|n| !n.is_empty()) {
+            None => Ok(None),
+            Some(n) => match lookup(n) {
+                Ok(tz) => Ok(Some(tz)),
+                Err(_) if lenient => Ok(None),
+                Err(e) => Err(e),
+            },
+        }
+    };
+    let (tz, source) = if let Some(tz) = parse(explicit)? {
+        (tz, ZoneSource::Argument)
+    } else if let Some(tz) = parse(stored)? {
+        (tz, ZoneSource::Preference)
+    } else if let Some(tz) = system_zone() {
+        (tz, ZoneSource::System)
+    } else {
+        (Tz::UTC, ZoneSource::Fallback)
+    };
+    Ok((tz, ResolvedZone { name: tz.name().to_string(), source }))
+}
+
+/// Parse an IANA zone name, suggesting the closest names when it isn't one.
+pub fn lookup(name: &str) -> Result<Tz, TimestampError> {
+    let name = name.trim();
+    name.parse::<Tz>().map_err(|_| TimestampError::UnknownTimezone {
+        name: name.to_string(),
+        suggestions: suggestions(name, chrono_tz::TZ_VARIANTS.iter().map(|tz| tz.name()), 3),
+    })
+}
+
+pub fn system_zone() -> Option<Tz> {
+    iana_time_zone::get_timezone().ok()?.parse().ok()
+}
+
+/// Long names for tz abbreviations. Abbreviations are not unique ("CST",
+/// "IST"), so each entry is scoped to zones whose name starts with the given
+/// prefix; the first matching entry wins.
+const LONG_NAMES: &[(&str, &str, &str)] = &[
+    ("UTC", "", "Coordinated Universal Time"),
+    ("GMT", "", "Greenwich Mean Time"),
+    ("EST", "", "Eastern Standard Time"),
+    ("EDT", "", "Eastern Daylight Time"),
+    ("CST", "America/Havana", "Cuba Standard Time"),
+    ("CDT", "America/Havana", "Cuba Daylight Time"),
+    ("CST", "Asia/", "China Standard Time"),
+    ("CST", "", "Central Standard Time"),
+    ("CDT", "", "Central Daylight Time"),
+    ("MST", "", "Mountain Standard Time"),
+    ("MDT", "", "Mountain Daylight Time"),
+    ("PST", "Asia/Manila", "Philippine Standard Time"),
+    ("PST", "", "Pacific Standard Time"),
+    ("PDT", "", "Pacific Daylight Time"),
+    ("AKST", "", "Alaska Standard Time"),
+    ("AKDT", "", "Alaska Daylight Time"),
+    ("HST", "", "Hawaii-Aleutian Standard Time"),
+    ("HDT", "", "Hawaii-Aleutian Daylight Time"),
+    ("AST", "", "Atlantic Standard Time"),
+    ("ADT", "", "Atlantic Daylight Time"),
+    ("NST", "", "Newfoundland Standard Time"),
+    ("NDT", "", "Newfoundland Daylight Time"),
+    ("BST", "Europe/", "British Summer Time"),
+    ("IST", "Europe/", "Irish Standard Time"),
+    ("IST", "Asia/Jerusalem", "Israel Standard Time"),
+    ("IST", "Asia/Tel_Aviv", "Israel Standard Time"),
+    ("IDT", "", "Israel Daylight Time"),
+    ("IST", "", "India Standard Time"),
+    ("WET", "", "Western European Standard Time"),
+    ("WEST", "", "Western European Summer Time"),
+    ("CET", "", "Central European Standard Time"),
+    ("CEST", "", "Central European Summer Time"),
+    ("MET", "", "Central European Standard Time"),
+    ("MEST", "", "Central European Summer Time"),
+    ("EET", "", "Eastern European Standard Time"),
+    ("EEST", "", "Eastern European Summer Time"),
+    ("MSK", "", "Moscow Standard Time"),
+    ("WAT", "", "West Africa Time"),
+    ("CAT", "", "Central Africa Time"),
+    ("EAT", "", "East Africa Time"),
+    ("SAST", "", "South Africa Standard Time"),
+    ("PKT", "", "Pakistan Standard Time"),
+    ("HKT", "", "Hong Kong Standard Time"),
+    ("JST", "", "Japan Standard Time"),
+    ("KST", "", "Korean Standard Time"),
+    ("WIB", "", "Western Indonesia Time"),
+    ("WITA", "", "Central Indonesia Time"),
+    ("WIT", "", "Eastern Indonesia Time"),
+    ("AEST", "", "Australian Eastern Standard Time"),
+    ("AEDT", "", "Australian Eastern Daylight Time"),
+    ("ACST", "", "Australian Central Standard Time"),
+    ("ACDT", "", "Australian Central Daylight Time"),
+    ("AWST", "", "Australian Western Standard Time"),
+    ("NZST", "", "New Zealand Standard Time"),
+    ("NZDT", "", "New Zealand Daylight Time"),
+    ("ChST", "", "Chamorro Standard Time"),
+    ("SST", "", "Samoa Standard Time"),
+];
+
+/// `+HH:MM` / `-HH:MM` offset from UTC at this instant.
+pub fn utc_offset(dt: &DateTime<Tz>) -> String {
+    let total = dt.offset().fix().local_minus_utc(); // seconds
+    let sign = if total >= 0 { '+' } else { '-' };
+    let total = total.abs();
+    format!("{}{:02}:{:02}", sign, total / 3600, (total % 3600) / 60)
+}
+
+/// Short name in effect at this instant, e.g. "EST" in January and "EDT" in July.
+pub fn abbreviation(dt: &DateTime<Tz>) -> String {
+    dt.offset().abbreviation().to_string()
+}
+
+/// Long name in effect at this instant, e.g. "Eastern Daylight Time".
+pub fn long_name(dt: &DateTime<Tz>) -> String {
+    let abbr = dt.offset().abbreviation();
+    let zone = dt.timezone().name();
+    LONG_NAMES
+        .iter()
+        .find(|(a, prefix, _)| *a == abbr && zone.starts_with(prefix))
+        .map(|(_, _, long)| long.to_string())
+        .unwrap_or_else(|| format!("GMT{}", utc_offset(dt)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(tz: Tz, month: u32) -> DateTime<Tz> {
+        tz.with_ymd_and_hms(2024, month, 15, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn numeric_abbreviations_get_gmt_long_names() {
+        let sao_paulo = at(chrono_tz::America::Sao_Paulo, 1);
+        assert_eq!(abbreviation(&sao_paulo), "-03");
+        assert_eq!(long_name(&sao_paulo), "GMT-03:00");
+        let dubai = at(chrono_tz::Asia::Dubai, 7);
+        assert_eq!(abbreviation(&dubai), "+04");
+        assert_eq!(long_name(&dubai), "GMT+04:00");
+        let kolkata = at(chrono_tz::Asia::Kolkata, 1);
+        assert_eq!((abbreviation(&kolkata), utc_offset(&kolkata)), ("IST".into(), "+05:30".into()));
+    }
+
+    #[test]
+    fn named_abbreviations_follow_dst() {
+        let (winter, summer) = (at(chrono_tz::America::New_York, 1), at(chrono_tz::America::New_York, 7));
+        assert_eq!((abbreviation(&winter), long_name(&winter)), ("EST".into(), "Eastern Standard Time".into()));
+        assert_eq!((abbreviation(&summer), long_name(&summer)), ("EDT".into(), "Eastern Daylight Time".into()));
+        assert_eq!(long_name(&at(chrono_tz::Asia::Shanghai, 1)), "China Standard Time");
+        assert_eq!(long_name(&at(chrono_tz::America::Chicago, 1)), "Central Standard Time");
+    }
+
+    #[test]
+    fn lookup_suggests_close_names() {
+        let suggested = |name: &str| match lookup(name) {
+            Err(TimestampError::UnknownTimezone { suggestions, .. }) => suggestions,
+            other => panic!("expected an unknown timezone error, got {:?}", other),
+        };
+        assert!(suggested("Europe/Pari").contains(&"Europe/Paris".to_string()));
+        assert!(suggested("America/New_Yrok").contains(&"America/New_York".to_string()));
+        assert!(suggested("tokyo").contains(&"Asia/Tokyo".to_string()));
+        assert!(suggested("Nowhere/At_All").is_empty());
+        assert_eq!(lookup(" Asia/Dubai ").unwrap(), chrono_tz::Asia::Dubai);
+    }
+
+    #[test]
+    fn resolution_order() {
+        let resolved = |explicit, stored, lenient| {
+            resolve(explicit, stored, lenient).map(|(tz, zone)| (tz, zone.source)).unwrap()
+        };
+        let (tokyo, paris) = (chrono_tz::Asia::Tokyo, chrono_tz::Europe::Paris);
+        assert_eq!(resolved(Some("Asia/Tokyo"), Some("Europe/Paris"), false), (tokyo, ZoneSource::Argument));
+        assert_eq!(resolved(None, Some("Europe/Paris"), false), (paris, ZoneSource::Preference));
+        assert_eq!(resolved(Some("  "), Some("Europe/Paris"), false), (paris, ZoneSource::Preference));
+        assert_eq!(resolved(Some("Bad/Zone"), Some("Europe/Paris"), true), (paris, ZoneSource::Preference));
+        assert!(resolve(Some("Bad/Zone"), Some("Europe/Paris"), false).is_err());
+        let fallback = match system_zone() {
+            Some(tz) => (tz, ZoneSource::System),
+            None => (Tz::UTC, ZoneSource::Fallback),
+        };
+        assert_eq!(resolved(None, None, false), fallback);
+        assert_eq!(resolved(None, Some("Bad/Zone"), true), fallback);
+    }
+}
@@ -8,8 +8,28 @@ import { Label } from '@/components/ui/label';
 import { toast } from 'sonner';
 import { invoke } from '@tauri-apps/api/core';
 import { getStore } from '@tauri-apps/plugin-store';
+import { describeError } from '@/lib/timestamp-error';
 
-type Preset = { name: string; format: string };
+// Mirrors Preset and PresetInput in src-tauri/src/presets.rs
+type Preset = {
+  id: string;
+  name: string;
+  format: string;
+  timezone: string | null;
+  locale: string | null;
+  tags: string[];
+  pinned: boolean;
+  sortOrder: number;
+  copyAs: 'text' | 'html';
+};
+type PresetInput = Omit<Preset, 'id' | 'sortOrder'>;
+
+const isDesktop = () =>
+  typeof window !== 'undefined' && typeof (window as unknown as { __TAURI__?: unknown }).__TAURI__ !== 'undefined';
+
+// The web build has no backend, so presets live in localStorage there
+const savePresetsLocally = (presets: Preset[]) =>
+  localStorage.setItem('thymestamp-presets', JSON.stringify(presets.map((p, i) => ({ ...p, sortOrder: i }))));
 
 export default function PreferencesPage() {
   const [presets, setPresets] = useState<Preset[]>([]);
@@ -18,24 +38,26 @@ export default function PreferencesPage() {
   const [locale, setLocale] = useState<string>('en-US');
   const [closeOnBlur, setCloseOnBlur] = useState<boolean>(true);
   const [alwaysOnTop, setAlwaysOnTop] = useState<boolean>(true);
+  const [copyAsHtml, setCopyAsHtml] = useState<boolean>(false);
   const [shortcut, setShortcut] = useState<string>('Cmd+Alt+T');
   const [newPresetName, setNewPresetName] = useState('');
   const [newPresetFormat, setNewPresetFormat] = useState('');
+  const [editing, setEditing] = useState<Preset | null>(null);
 
   useEffect(() => {
     // Load from store via JS API
     (async () => {
       try {
-        const isDesktop = typeof window !== 'undefined' && typeof (window as unknown as { __TAURI__?: unknown }).__TAURI__ !== 'undefined';
-        if (isDesktop) {
+        if (isDesktop()) {
+          setPresets(await invoke<Preset[]>('list_presets'));
           const store = await getStore('prefs.json');
           if (store) {
-            setPresets((await store.get('presets')) as Preset[] || []);
             setDefaultFormat((await store.get('defaultFormat')) as string || '{day}, {month} {date} {time} {period}');
             setTimezone((await store.get('timezone')) as string || 'UTC');
             setLocale((await store.get('locale')) as string || 'en-US');
             setCloseOnBlur((await store.get('closeOnBlur')) as boolean ?? true);
             setAlwaysOnTop((await store.get('alwaysOnTop')) as boolean ?? true);
+            setCopyAsHtml((await store.get('copyAs')) === 'html');
             setShortcut((await store.get('shortcut')) as string || 'Cmd+Alt+T');
           }
         } else {
@@ -46,8 +68,16 @@ export default function PreferencesPage() {
           setLocale(ls('locale') || 'en-US');
           setCloseOnBlur(ls('closeOnBlur') === 'true');
           setAlwaysOnTop(ls('alwaysOnTop') !== 'false');
+          setCopyAsHtml(ls('copyAs') === 'html');
           setShortcut(ls('shortcut') || '');
-          try { setPresets(JSON.parse(ls('presets') || '[]')); } catch {}
+          try {
+            // Older builds stored bare { name, format } pairs
+            const stored = JSON.parse(ls('presets') || '[]') as Partial<Preset>[];
+            setPresets(stored.map((p, i): Preset => ({
+              timezone: null, locale: null, tags: [], pinned: false, copyAs: 'text',
+              ...p, name: p.name ?? '', format: p.format ?? '', id: p.id || crypto.randomUUID(), sortOrder: i,
+            })));
+          } catch {}
         }
       } catch (e) {
         console.error(e);
@@ -56,16 +86,15 @@ export default function PreferencesPage() {
   }, []);
 
   const saveAll = async () => {
-  const isDesktop = typeof window !== 'undefined' && typeof (window as unknown as { __TAURI__?: unknown }).__TAURI__ !== 'undefined';
-    if (isDesktop) {
+    if (isDesktop()) {
       const store = await getStore('prefs.json');
       if (store) {
-        await store.set('presets', presets);
         await store.set('defaultFormat', defaultFormat);
         await store.set('timezone', timezone);
         await store.set('locale', locale);
         await store.set('closeOnBlur', closeOnBlur);
         await store.set('alwaysOnTop', alwaysOnTop);
+        await store.set('copyAs', copyAsHtml ? 'html' : 'text');
         await store.set('shortcut', shortcut);
         await store.save();
       }
@@ -76,8 +105,8 @@ export default function PreferencesPage() {
       s('locale', locale);
       s('closeOnBlur', String(closeOnBlur));
       s('alwaysOnTop', String(alwaysOnTop));
+      s('copyAs', copyAsHtml ? 'html' : 'text');
       s('shortcut', shortcut);
-      s('presets', JSON.stringify(presets));
     }
   // apply immediate effects
   await invoke('set_close_on_blur', { value: closeOnBlur });
@@ -95,27 +124,85 @@ export default function PreferencesPage() {
     }
   };
 
-  const removePreset = (idx: number) => {
-    setPresets(p => p.filter((_, i) => i !== idx));
+  // Presets are saved as soon as they change, through the preset commands,
+  // so the backend can validate them and keep ids and sort order
+  const removePreset = async (preset: Preset) => {
+    try {
+      const next = presets.filter(p => p.id !== preset.id);
+      if (isDesktop()) {
+        await invoke('delete_preset', { id: preset.id });
+      } else {
+        savePresetsLocally(next);
+      }
+      setPresets(next);
+      if (editing?.id === preset.id) cancelEdit();
+    } catch (e) {
+      toast.error(describeError(e));
+    }
   };
 
-  const movePreset = (idx: number, dir: -1 | 1) => {
-    setPresets(p => {
-      const np = [...p];
-      const j = idx + dir;
-      if (j < 0 || j >= np.length) return p;
-      [np[idx], np[j]] = [np[j], np[idx]];
-      return np;
-    });
+  const movePreset = async (idx: number, dir: -1 | 1) => {
+    const j = idx + dir;
+    if (j < 0 || j >= presets.length) return;
+    const next = [...presets];
+    [next[idx], next[j]] = [next[j], next[idx]];
+    try {
+      if (isDesktop()) {
+        setPresets(await invoke<Preset[]>('reorder_presets', { ids: next.map(p => p.id) }));
+      } else {
+        savePresetsLocally(next);
+        setPresets(next);
+      }
+    } catch (e) {
+      toast.error(describeError(e));
+    }
   };
 
-  const addPreset = () => {
-    if (!newPresetName.trim() || !newPresetFormat.trim()) return;
-    setPresets(p => [{ name: newPresetName.trim(), format: newPresetFormat.trim() }, ...p]);
+  const editPreset = (preset: Preset) => {
+    setEditing(preset);
+    setNewPresetName(preset.name);
+    setNewPresetFormat(preset.format);
+  };
+
+  const cancelEdit = () => {
+    setEditing(null);
     setNewPresetName('');
     setNewPresetFormat('');
   };
 
+  const submitPreset = async () => {
+    if (!newPresetName.trim() || !newPresetFormat.trim()) return;
+    const input: PresetInput = {
+      name: newPresetName.trim(),
+      format: newPresetFormat.trim(),
+      timezone: editing?.timezone ?? null,
+      locale: editing?.locale ?? null,
+      tags: editing?.tags ?? [],
+      pinned: editing?.pinned ?? false,
+      copyAs: editing?.copyAs ?? 'text',
+    };
+    try {
+      if (isDesktop()) {
+        if (editing) {
+          const updated = await invoke<Preset>('update_preset', { id: editing.id, preset: input });
+          setPresets(p => p.map(q => (q.id === updated.id ? updated : q)));
+        } else {
+          const created = await invoke<Preset>('create_preset', { preset: input });
+          setPresets(p => [...p, created]);
+        }
+      } else {
+        const next = editing
+          ? presets.map(q => (q.id === editing.id ? { ...q, ...input } : q))
+          : [...presets, { ...input, id: crypto.randomUUID(), sortOrder: presets.length }];
+        savePresetsLocally(next);
+        setPresets(next);
+      }
+      cancelEdit();
+    } catch (e) {
+      toast.error(describeError(e));
+    }
+  };
+
   return (
     <div className="p-6 space-y-4 max-w-2xl mx-auto">
       <h1 className="text-xl font-semibold">Preferences</h1>
@@ -126,12 +213,13 @@ export default function PreferencesPage() {
             <Input className="col-span-1" placeholder="Name" value={newPresetName} onChange={e => setNewPresetName(e.target.value)} />
             <Input className="col-span-2" placeholder="Format" value={newPresetFormat} onChange={e => setNewPresetFormat(e.target.value)} />
           </div>
-          <div>
-            <Button size="sm" onClick={addPreset}>Add Preset</Button>
+          <div className="flex gap-2">
+            <Button size="sm" onClick={submitPreset}>{editing ? 'Save Preset' : 'Add Preset'}</Button>
+            {editing && <Button size="sm" variant="outline" onClick={cancelEdit}>Cancel</Button>}
           </div>
           <div className="space-y-2">
             {presets.map((p, i) => (
-              <div key={`${p.name}-${i}`} className="flex items-center gap-2 text-sm">
+              <div key={p.id} className="flex items-center gap-2 text-sm">
                 <div className="flex-1 min-w-0">
                   <div className="font-medium truncate">{p.name}</div>
                   <div className="font-mono text-xs text-muted-foreground truncate">{p.format}</div>
@@ -139,7 +227,8 @@ export default function PreferencesPage() {
                 <div className="flex gap-1">
                   <Button size="sm" variant="outline" onClick={() => movePreset(i, -1)}>↑</Button>
                   <Button size="sm" variant="outline" onClick={() => movePreset(i, 1)}>↓</Button>
-                  <Button size="sm" variant="destructive" onClick={() => removePreset(i)}>Remove</Button>
+                  <Button size="sm" variant="outline" onClick={() => editPreset(p)}>Edit</Button>
+                  <Button size="sm" variant="destructive" onClick={() => removePreset(p)}>Remove</Button>
                 </div>
               </div>
             ))}
@@ -171,6 +260,10 @@ export default function PreferencesPage() {
             <input type="checkbox" checked={alwaysOnTop} onChange={e => setAlwaysOnTop(e.target.checked)} />
             Always on top
           </label>
+          <label className="flex items-center gap-2 text-sm">
+            <input type="checkbox" checked={copyAsHtml} onChange={e => setCopyAsHtml(e.target.checked)} />
+            Tray copies as HTML
+          </label>
         </div>
       </Card>
 
@@ -6,15 +6,20 @@ import { Button } from '@/components/ui/button';
 import { Card } from '@/components/ui/card';
 import { Copy, RefreshCw } from 'lucide-react';
 import { toast } from 'sonner';
+import { describeError, type TimestampError } from '@/lib/timestamp-error';
 
 interface TimestampFormat {
   name: string;
   format: string;
   value: string;
+  timezone: { name: string };
+  // Set when this entry couldn't be rendered; value is then empty
+  error?: TimestampError;
 }
 
 export default function TrayInterface() {
   const [formats, setFormats] = useState<TimestampFormat[]>([]);
+  const [loadError, setLoadError] = useState<string | null>(null);
   const [loading, setLoading] = useState(true);
   const [lastUpdate, setLastUpdate] = useState<Date>(new Date());
 
@@ -32,22 +37,27 @@ export default function TrayInterface() {
     try {
       const result = await invoke<TimestampFormat[]>('get_current_formats');
       setFormats(result);
+      setLoadError(null);
     } catch (error) {
       console.error('Failed to load formats:', error);
+      setLoadError(describeError(error));
     } finally {
       setLoading(false);
     }
   };
 
-  const copyToClipboard = async (value: string, name: string) => {
+  const copyToClipboard = async (format: TimestampFormat) => {
     try {
-      await invoke('copy_to_clipboard', { text: value });
-      toast.success(`${name} copied!`, {
+      await invoke('copy_to_clipboard', {
+        text: format.value,
+        context: { preset: format.name, timezone: format.timezone.name, source: 'tray' },
+      });
+      toast.success(`${format.name} copied!`, {
         duration: 2000,
       });
     } catch (error) {
       console.error('Copy failed:', error);
-      toast.error('Failed to copy to clipboard');
+      toast.error(describeError(error));
     }
   };
 
@@ -79,6 +89,10 @@ export default function TrayInterface() {
             <RefreshCw className={`h-3 w-3 ${loading ? 'animate-spin' : ''}`} />
           </Button>
         </div>
+
+        {loadError && (
+          <div className="text-xs text-destructive">{loadError}</div>
+        )}
         
         {formats.map((format) => (
           <Card key={format.format} className="p-2 hover:bg-muted/50 transition-colors">
@@ -87,15 +101,22 @@ export default function TrayInterface() {
                 <div className="text-xs text-muted-foreground font-medium">
                   {format.name}
                 </div>
-                <div className="text-xs font-mono truncate mt-1 select-all">
-                  {format.value}
-                </div>
+                {format.error ? (
+                  <div className="text-xs text-destructive mt-1">
+                    {describeError(format.error)}
+                  </div>
+                ) : (
+                  <div className="text-xs font-mono truncate mt-1 select-all">
+                    {format.value}
+                  </div>
+                )}
               </div>
               <Button
                 size="sm"
                 variant="ghost"
                 className="h-6 w-6 p-0 ml-2 shrink-0"
-                onClick={() => copyToClipboard(format.value, format.name)}
+                onClick={() => copyToClipboard(format)}
+                disabled={!!format.error}
               >
                 <Copy className="h-3 w-3" />
               </Button>
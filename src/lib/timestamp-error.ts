@@ -0,0 +1,28 @@
+// Mirrors TimestampError in src-tauri/src/error.rs
+export type TimestampError =
+  | { kind: 'unknownTimezone'; name: string; suggestions: string[] }
+  | { kind: 'invalidFormat'; format: string; diagnostics: { message: string }[] }
+  | { kind: 'invalidInstant'; message: string }
+  | { kind: 'invalidSetting'; name: string; message: string }
+  | { kind: 'clipboard'; message: string };
+
+export function describeError(error: unknown): string {
+  if (typeof error === 'string') return error;
+  if (typeof error !== 'object' || error === null || !('kind' in error)) {
+    return String(error);
+  }
+  const e = error as TimestampError;
+  switch (e.kind) {
+    case 'unknownTimezone':
+      return e.suggestions.length > 0
+        ? `Unknown timezone "${e.name}" (did you mean ${e.suggestions.join(', ')}?)`
+        : `Unknown timezone "${e.name}"`;
+    case 'invalidFormat':
+      return `Invalid format: ${e.diagnostics.map((d) => d.message).join('; ')}`;
+    case 'invalidSetting':
+      return `Invalid ${e.name}: ${e.message}`;
+    case 'invalidInstant':
+    case 'clipboard':
+      return e.message;
+  }
+}